@@ -8,9 +8,30 @@ use iced::{Application,
             Color,
             Rectangle,
             Settings,
+            window,
         };
+use iced_test::raster::{Pixmap, Shape, Surface};
 
 pub fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if let [command, path] = args.as_slice() {
+        if command == "snapshot" {
+            let (width, height) = window::Settings::default().size;
+            let lienzo = Lienzo::new(()).0;
+
+            let mut pixmap = Pixmap::new(width, height);
+            lienzo.draw_circle(&mut pixmap);
+
+            if let Err(error) = pixmap.save_png(path) {
+                eprintln!("Could not write {}: {}", path, error);
+                std::process::exit(1);
+            }
+
+            return;
+        }
+    }
+
     Lienzo::run(Settings {
         antialiasing: true,
         ..Settings::default()
@@ -63,14 +84,18 @@ struct Circulo {
     radius: f32,
 }
 
+impl Lienzo {
+    fn draw_circle(&self, frame: &mut impl Surface) {
+        let cir = Shape::Circle { center: frame.center(), radius: self.circulo.radius };
+
+        frame.fill(&cir, Color::from_rgb8(0xF9, 0xD7, 0x1C));
+    }
+}
+
 impl<Message> canvas::Program<Message> for Lienzo {
     fn draw(&self, bounds: Rectangle, _cursor: canvas::Cursor) -> Vec<canvas::Geometry> {
 
-        let circle = self.circle.draw(bounds.size(), |frame| {
-            let cir = canvas::Path::circle(frame.center(), self.circulo.radius);
-
-            frame.fill(&cir, Color::from_rgb8(0xF9, 0xD7, 0x1C));
-        });
+        let circle = self.circle.draw(bounds.size(), |frame| self.draw_circle(frame));
 
         vec![circle]
 
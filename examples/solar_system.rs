@@ -1,12 +1,33 @@
 use std::time::Instant;
 use iced::{
-    canvas::{self, Cursor, Path, Stroke},
+    canvas::{self, Cursor, Stroke},
     executor, time, window, Application, Canvas, Color, Command, Element,
     Length, Point, Rectangle, Settings, Size, Subscription, Vector
 };
+use iced_test::raster::{Pixmap, Shape, Surface};
 
 pub fn main() -> iced::Result {
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if let [command, path] = args.as_slice() {
+        if command == "snapshot" {
+            let (width, height) = window::Settings::default().size;
+            let state = State::new();
+
+            let mut pixmap = Pixmap::new(width, height);
+            pixmap.with_save(|pixmap| state.draw_space(pixmap));
+            pixmap.with_save(|pixmap| state.draw_system(pixmap));
+
+            if let Err(error) = pixmap.save_png(path) {
+                eprintln!("Could not write {}: {}", path, error);
+                std::process::exit(1);
+            }
+
+            return Ok(());
+        }
+    }
+
     SolarSystem::run(Settings {
         antialiasing: true,
         ..Settings::default()
@@ -111,57 +132,67 @@ impl State {
     }
 }
 
-impl<Message> canvas::Program<Message> for State {
-    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<canvas::Geometry> {
-        use std::f32::consts::PI;
+impl State {
+    fn draw_space(&self, frame: &mut impl Surface) {
+        let space = Shape::Rectangle {
+            top_left: Point::new(0.0, 0.0),
+            size: frame.size(),
+        };
 
-        let background = self.space_cache.draw(bounds.size(), |frame| {
-            let space = Path::rectangle(Point::new(0.0, 0.0), frame.size());
+        let stars = Shape::Rectangles(
+            self.stars.iter().map(|(p, size)| (*p, Size::new(*size, *size))).collect()
+        );
 
-            let stars = Path::new(|path| {
-                for (p, size) in &self.stars {
-                    path.rectangle(*p, Size::new(*size, *size));
-                }
-            });
+        frame.fill(&space, Color::BLACK);
+        frame.translate(frame.center() - Point::ORIGIN);
+        frame.fill(&stars, Color::WHITE);
+    }
 
-            frame.fill(&space, Color::BLACK);
-            frame.translate(frame.center() - Point::ORIGIN);
-            frame.fill(&stars, Color::WHITE);
-        });
+    fn draw_system(&self, frame: &mut impl Surface) {
+        use std::f32::consts::PI;
+
+        let center = frame.center();
 
-        let system = self.system_cache.draw(bounds.size(), |frame| {
-            let center = frame.center();
+        let sun = Shape::Circle { center, radius: Self::SUN_RADIUS };
+        let orbit = Shape::Circle { center, radius: Self::ORBIT_RADIUS };
 
-            let sun = Path::circle(center, Self::SUN_RADIUS);
-            let orbit = Path::circle(center, Self::ORBIT_RADIUS);
+        frame.fill(&sun, Color::from_rgb8(0xF9, 0xD7, 0x1C));
+        frame.stroke(&orbit, Stroke {width:1.0, color: Color::from_rgba8(0, 153, 255, 0.1), ..Stroke::default()});
 
-            frame.fill(&sun, Color::from_rgb8(0xF9, 0xD7, 0x1C));
-            frame.stroke(&orbit, Stroke {width:1.0, color: Color::from_rgba8(0, 153, 255, 0.1), ..Stroke::default()});
+        let elapsed = self.now - self.start;
+        let rotation = (2.0 * PI / 60.0) * elapsed.as_secs() as f32 + (2.0 * PI / 60_000.0) * elapsed.subsec_millis() as f32;
 
-            let elapsed = self.now - self.start;
-            let rotation = (2.0 * PI / 60.0) * elapsed.as_secs() as f32 + (2.0 * PI / 60_000.0) * elapsed.subsec_millis() as f32;
+        frame.with_save(|frame2| {
+            frame2.translate(Vector::new(center.x, center.y));
+            frame2.rotate(rotation);
+            frame2.translate(Vector::new(Self::ORBIT_RADIUS, 0.0));
 
-            frame.with_save(|frame2| {
-                frame2.translate(Vector::new(center.x, center.y));
-                frame2.rotate(rotation);
-                frame2.translate(Vector::new(Self::ORBIT_RADIUS, 0.0));
+            let earth = Shape::Circle { center: Point::ORIGIN, radius: Self::EARTH_RADIUS };
+            let shadow = Shape::Rectangle {
+                top_left: Point::new(0.0, -Self::EARTH_RADIUS),
+                size: Size::new(Self::EARTH_RADIUS * 4.0, Self::EARTH_RADIUS * 2.0),
+            };
 
-                let earth = Path::circle(Point::ORIGIN, Self::EARTH_RADIUS);
-                let shadow = Path::rectangle(Point::new(0.0, -Self::EARTH_RADIUS), Size::new(Self::EARTH_RADIUS * 4.0, Self::EARTH_RADIUS * 2.0));
+            frame2.fill(&earth, Color::from_rgb8(0x6B, 0x93, 0xD6));
 
-                frame2.fill(&earth, Color::from_rgb8(0x6B, 0x93, 0xD6));
+            frame2.with_save(|frame3| {
+                frame3.rotate(rotation * 10.0);
+                frame3.translate(Vector::new(0.0, Self::MOON_DISTANCE));
 
-                frame2.with_save(|frame3| {
-                    frame3.rotate(rotation * 10.0);
-                    frame3.translate(Vector::new(0.0, Self::MOON_DISTANCE));
+                let moon = Shape::Circle { center: Point::ORIGIN, radius: Self::MOON_RADIUS };
+                frame3.fill(&moon, Color::WHITE);
+            });
 
-                    let moon = Path::circle(Point::ORIGIN, Self::MOON_RADIUS);
-                    frame3.fill(&moon, Color::WHITE);
-                });
+            frame2.fill(&shadow, Color {a: 0.7, ..Color::BLACK});
+        })
+    }
+}
+
+impl<Message> canvas::Program<Message> for State {
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<canvas::Geometry> {
+        let background = self.space_cache.draw(bounds.size(), |frame| self.draw_space(frame));
 
-                frame2.fill(&shadow, Color {a: 0.7, ..Color::BLACK});
-            })
-        });
+        let system = self.system_cache.draw(bounds.size(), |frame| self.draw_system(frame));
 
         vec![background, system]
     }
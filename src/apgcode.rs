@@ -0,0 +1,321 @@
+use std::fmt;
+
+use crate::{Cell, Life};
+
+const MAX_PERIOD: usize = 1000;
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+type Phase = Vec<(isize, isize)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    StillLife,
+    Oscillator,
+    Spaceship,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Empty,
+    InvalidPrefix(String),
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Empty => write!(f, "empty apgcode"),
+            Error::InvalidPrefix(prefix) => write!(f, "invalid apgcode prefix `{}`", prefix),
+            Error::InvalidCharacter(c) => write!(f, "invalid character `{}` in apgcode", c),
+        }
+    }
+}
+
+// Canonical apgcode of a still life, oscillator or spaceship: the shortest
+// (then lexicographically smallest) encoding across every phase and every
+// rotation or reflection of the object.
+pub fn encode(life: &Life) -> Option<String> {
+    let (kind, phases) = classify(life)?;
+
+    let code = phases
+        .iter()
+        .flat_map(|phase| {
+            (0..8).map(move |symmetry| wechsler(&normalize(transform(phase, symmetry))))
+        })
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))?;
+
+    let prefix = match kind {
+        Kind::StillLife => format!("xs{}", life.cells.len()),
+        Kind::Oscillator => format!("xp{}", phases.len()),
+        Kind::Spaceship => format!("xq{}", phases.len()),
+    };
+
+    Some(format!("{}_{}", prefix, code))
+}
+
+pub fn decode(apgcode: &str) -> Result<Vec<Cell>, Error> {
+    let code = match apgcode.find('_') {
+        Some(index) => {
+            let prefix = &apgcode[..index];
+
+            if !is_valid_prefix(prefix) {
+                return Err(Error::InvalidPrefix(String::from(prefix)));
+            }
+
+            &apgcode[index + 1..]
+        },
+        None => apgcode,
+    };
+
+    if code.is_empty() {
+        return Err(Error::Empty);
+    }
+
+    let mut cells = Vec::new();
+    let mut chars = code.chars();
+    let (mut x, mut strip) = (0, 0);
+
+    while let Some(c) = chars.next() {
+        match c {
+            'w' => x += 2,
+            'x' => x += 3,
+            'y' => {
+                let next = chars.next().ok_or(Error::InvalidCharacter(c))?;
+                let run = digit(next).ok_or(Error::InvalidCharacter(next))?;
+
+                x += 4 + run as isize;
+            },
+            'z' => {
+                x = 0;
+                strip += 5;
+            },
+            _ => {
+                let value = digit(c)
+                    .filter(|value| *value < 32)
+                    .ok_or(Error::InvalidCharacter(c))?;
+
+                for row in 0..5 {
+                    if value & (1 << row) != 0 {
+                        cells.push(Cell { i: strip + row, j: x });
+                    }
+                }
+
+                x += 1;
+            },
+        }
+    }
+
+    Ok(cells)
+}
+
+pub fn classify(life: &Life) -> Option<(Kind, Vec<Phase>)> {
+    if life.cells.is_empty() {
+        return None;
+    }
+
+    let start = points(life);
+    let (start_x, start_y) = origin(&start);
+    let start = normalize(start);
+
+    let mut life = life.clone();
+    let mut phases = vec![start.clone()];
+
+    for period in 1..=MAX_PERIOD {
+        life.tick();
+
+        let current = points(&life);
+        let (x, y) = origin(&current);
+        let current = normalize(current);
+
+        if current == start {
+            let kind = if x != start_x || y != start_y {
+                Kind::Spaceship
+            } else if period == 1 {
+                Kind::StillLife
+            } else {
+                Kind::Oscillator
+            };
+
+            return Some((kind, phases));
+        }
+
+        phases.push(current);
+    }
+
+    None
+}
+
+fn is_valid_prefix(prefix: &str) -> bool {
+    let mut chars = prefix.chars();
+
+    chars.next() == Some('x')
+        && matches!(chars.next(), Some('s') | Some('p') | Some('q'))
+        && chars.clone().next().is_some()
+        && chars.all(|c| c.is_ascii_digit())
+}
+
+fn digit(c: char) -> Option<u8> {
+    DIGITS.iter().position(|d| *d as char == c).map(|value| value as u8)
+}
+
+fn points(life: &Life) -> Phase {
+    life.cells.iter().map(|cell| (cell.j, cell.i)).collect()
+}
+
+fn origin(points: &[(isize, isize)]) -> (isize, isize) {
+    let x = points.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let y = points.iter().map(|(_, y)| *y).min().unwrap_or(0);
+
+    (x, y)
+}
+
+fn normalize(mut points: Phase) -> Phase {
+    let (x, y) = origin(&points);
+
+    for point in points.iter_mut() {
+        *point = (point.0 - x, point.1 - y);
+    }
+
+    points.sort_unstable();
+    points
+}
+
+fn transform(points: &[(isize, isize)], symmetry: u8) -> Phase {
+    points
+        .iter()
+        .map(|&(x, y)| match symmetry {
+            0 => (x, y),
+            1 => (-x, y),
+            2 => (x, -y),
+            3 => (-x, -y),
+            4 => (y, x),
+            5 => (-y, x),
+            6 => (y, -x),
+            _ => (-y, -x),
+        })
+        .collect()
+}
+
+// Expects normalized points: five-row strips separated by `z`, one character
+// per column, with runs of blank columns compressed as `w`, `x` and `y?`.
+fn wechsler(points: &[(isize, isize)]) -> String {
+    let width = points.iter().map(|(x, _)| x + 1).max().unwrap_or(0) as usize;
+    let height = points.iter().map(|(_, y)| y + 1).max().unwrap_or(0) as usize;
+
+    let mut strips = vec![vec![0u8; width]; height.div_ceil(5)];
+
+    for &(x, y) in points {
+        strips[y as usize / 5][x as usize] |= 1 << (y % 5);
+    }
+
+    strips
+        .iter()
+        .map(|strip| {
+            let length = strip.iter().rposition(|value| *value != 0).map_or(0, |last| last + 1);
+
+            compress(&strip[..length])
+        })
+        .collect::<Vec<_>>()
+        .join("z")
+}
+
+fn compress(columns: &[u8]) -> String {
+    let mut code = String::new();
+    let mut zeros = 0;
+
+    for &value in columns.iter().chain(std::iter::once(&u8::MAX)) {
+        if value == 0 {
+            zeros += 1;
+            continue;
+        }
+
+        while zeros >= 4 {
+            let run = zeros.min(39);
+
+            code.push('y');
+            code.push(DIGITS[run - 4] as char);
+            zeros -= run;
+        }
+
+        match zeros {
+            3 => code.push('x'),
+            2 => code.push('w'),
+            1 => code.push('0'),
+            _ => {},
+        }
+
+        zeros = 0;
+
+        if value != u8::MAX {
+            code.push(DIGITS[value as usize] as char);
+        }
+    }
+
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(code: &str) -> Life {
+        decode(code).expect("valid apgcode").into_iter().collect()
+    }
+
+    #[test]
+    fn encodes_known_objects() {
+        for code in &["xs4_33", "xs6_696", "xp2_7", "xq4_153", "xq4_6frc"] {
+            assert_eq!(encode(&life(code)).as_deref(), Some(*code));
+        }
+    }
+
+    #[test]
+    fn encoding_is_minimal_across_orientations() {
+        let glider = points(&life("xq4_153"));
+
+        for symmetry in 0..8 {
+            let cells: Life = transform(&glider, symmetry)
+                .into_iter()
+                .map(|(j, i)| Cell { i, j })
+                .collect();
+
+            assert_eq!(encode(&cells).as_deref(), Some("xq4_153"));
+        }
+    }
+
+    #[test]
+    fn classifies_objects() {
+        let kind = |code| classify(&life(code)).map(|(kind, phases)| (kind, phases.len()));
+
+        assert_eq!(kind("xs4_33"), Some((Kind::StillLife, 1)));
+        assert_eq!(kind("xp2_7"), Some((Kind::Oscillator, 2)));
+        assert_eq!(kind("xq4_153"), Some((Kind::Spaceship, 4)));
+        assert_eq!(classify(&Life::default()).map(|(kind, _)| kind), None);
+    }
+
+    #[test]
+    fn decodes_blank_runs_and_strips() {
+        let cells = |code| {
+            let mut cells: Vec<(isize, isize)> = decode(code).unwrap().iter().map(|cell| (cell.i, cell.j)).collect();
+            cells.sort_unstable();
+            cells
+        };
+
+        assert_eq!(cells("1w1"), vec![(0, 0), (0, 3)]);
+        assert_eq!(cells("1x1"), vec![(0, 0), (0, 4)]);
+        assert_eq!(cells("1y01"), vec![(0, 0), (0, 5)]);
+        assert_eq!(cells("1z1"), vec![(0, 0), (5, 0)]);
+    }
+
+    #[test]
+    fn rejects_malformed_codes() {
+        assert_eq!(decode(""), Err(Error::Empty));
+        assert_eq!(decode("xs4_"), Err(Error::Empty));
+        assert_eq!(decode("xa4_33"), Err(Error::InvalidPrefix(String::from("xa4"))));
+        assert_eq!(decode("xs_33"), Err(Error::InvalidPrefix(String::from("xs"))));
+        assert_eq!(decode("xs4_3!"), Err(Error::InvalidCharacter('!')));
+        assert_eq!(decode("xs4_3y"), Err(Error::InvalidCharacter('y')));
+        assert_eq!(decode("xs4_3y!"), Err(Error::InvalidCharacter('!')));
+        assert_eq!(decode("xs4_3A"), Err(Error::InvalidCharacter('A')));
+    }
+}
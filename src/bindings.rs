@@ -0,0 +1,375 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use iced::keyboard::{KeyCode, Modifiers};
+
+use crate::tools::Tool;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    TogglePlayback,
+    Step,
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    ZoomIn,
+    ZoomOut,
+    Fit,
+    Undo,
+    Redo,
+    ToggleLines,
+    Tool(Tool),
+}
+
+impl Action {
+    fn parse(name: &str) -> Option<Action> {
+        let action = match name {
+            "play_pause" => Action::TogglePlayback,
+            "step" => Action::Step,
+            "pan_up" => Action::PanUp,
+            "pan_down" => Action::PanDown,
+            "pan_left" => Action::PanLeft,
+            "pan_right" => Action::PanRight,
+            "zoom_in" => Action::ZoomIn,
+            "zoom_out" => Action::ZoomOut,
+            "fit" => Action::Fit,
+            "undo" => Action::Undo,
+            "redo" => Action::Redo,
+            "grid" => Action::ToggleLines,
+            _ => {
+                let index: usize = name.strip_prefix("tool_")?.parse().ok()?;
+
+                return Tool::ALL.get(index.checked_sub(1)?).copied().map(Action::Tool);
+            },
+        };
+
+        Some(action)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    key_code: KeyCode,
+    modifiers: Modifiers,
+}
+
+impl Binding {
+    const fn key(key_code: KeyCode) -> Self {
+        Binding {
+            key_code,
+            modifiers: Modifiers { shift: false, control: false, alt: false, logo: false },
+        }
+    }
+
+    const fn control(key_code: KeyCode, shift: bool) -> Self {
+        Binding {
+            key_code,
+            modifiers: Modifiers { shift, control: true, alt: false, logo: false },
+        }
+    }
+
+    // Parses `Ctrl+Shift+Z`-style combinations of modifiers and a key name.
+    fn parse(text: &str) -> Option<Binding> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+
+        // `+` and `Ctrl++` name the `+` key itself, while `Ctrl+` has no key.
+        if parts.len() > 1 && parts[parts.len() - 1].is_empty() {
+            parts.pop();
+
+            if !parts.last()?.is_empty() {
+                return None;
+            }
+
+            *parts.last_mut()? = "Plus";
+        }
+
+        let (key, modifiers) = parts.split_last()?;
+
+        let mut binding = Binding::key(key_code(key)?);
+
+        for modifier in modifiers {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => binding.modifiers.control = true,
+                "shift" => binding.modifiers.shift = true,
+                "alt" => binding.modifiers.alt = true,
+                "logo" | "super" | "cmd" => binding.modifiers.logo = true,
+                _ => return None,
+            }
+        }
+
+        Some(binding)
+    }
+
+    // Shift is only compared when the binding asks for it, so `+` typed as
+    // Shift+= still matches, but other modifiers must be exact.
+    fn matches(&self, key_code: KeyCode, modifiers: Modifiers) -> bool {
+        self.key_code == key_code
+            && self.modifiers.control == modifiers.control
+            && self.modifiers.alt == modifiers.alt
+            && self.modifiers.logo == modifiers.logo
+            && (!self.modifiers.shift || modifiers.shift)
+    }
+
+    fn specificity(&self) -> usize {
+        [self.modifiers.shift, self.modifiers.control, self.modifiers.alt, self.modifiers.logo]
+            .iter()
+            .filter(|pressed| **pressed)
+            .count()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    bindings: Vec<(Binding, Action)>,
+}
+
+// The file comes from `LIFE_BINDINGS`, falling back to `bindings.cfg` in the
+// working directory.
+pub fn path() -> PathBuf {
+    std::env::var_os("LIFE_BINDINGS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("bindings.cfg"))
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let mut bindings = vec![
+            (Binding::key(KeyCode::Space), Action::TogglePlayback),
+            (Binding::key(KeyCode::N), Action::Step),
+            (Binding::key(KeyCode::Up), Action::PanUp),
+            (Binding::key(KeyCode::Down), Action::PanDown),
+            (Binding::key(KeyCode::Left), Action::PanLeft),
+            (Binding::key(KeyCode::Right), Action::PanRight),
+            (Binding::key(KeyCode::Plus), Action::ZoomIn),
+            (Binding::key(KeyCode::Equals), Action::ZoomIn),
+            (Binding::key(KeyCode::NumpadAdd), Action::ZoomIn),
+            (Binding::key(KeyCode::Minus), Action::ZoomOut),
+            (Binding::key(KeyCode::NumpadSubtract), Action::ZoomOut),
+            (Binding::key(KeyCode::F), Action::Fit),
+            (Binding::key(KeyCode::G), Action::ToggleLines),
+            (Binding::key(KeyCode::Z), Action::Undo),
+            (Binding::key(KeyCode::Y), Action::Redo),
+            (Binding::control(KeyCode::Z, false), Action::Undo),
+            (Binding::control(KeyCode::Z, true), Action::Redo),
+            (Binding::control(KeyCode::Y, false), Action::Redo),
+        ];
+
+        let digits = [
+            KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+            KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8,
+        ];
+
+        bindings.extend(digits.iter().zip(Tool::ALL.iter()).map(|(key, tool)| {
+            (Binding::key(*key), Action::Tool(*tool))
+        }));
+
+        Bindings { bindings }
+    }
+}
+
+impl Bindings {
+    // Reads `action = key` lines, e.g. `undo = Ctrl+Z`. An action named in
+    // the file loses its default keys; the rest keep theirs. A missing file
+    // gives the defaults, and bad lines are reported and skipped.
+    pub fn load(path: impl AsRef<Path>) -> (Bindings, Vec<String>) {
+        let mut bindings = Bindings::default();
+        let mut errors = Vec::new();
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return (bindings, errors),
+            Err(error) => {
+                errors.push(format!("{}: {}", path.as_ref().display(), error));
+
+                return (bindings, errors);
+            },
+        };
+
+        let mut configured: Vec<(Binding, Action)> = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| format!("{}:{}: {}", path.as_ref().display(), number + 1, message);
+
+            let (name, key) = match line.find('=') {
+                Some(index) => (line[..index].trim(), line[index + 1..].trim()),
+                None => {
+                    errors.push(error("expected `action = key`"));
+                    continue;
+                },
+            };
+
+            match (Action::parse(name), Binding::parse(key)) {
+                (Some(action), Some(binding)) => configured.push((binding, action)),
+                (None, _) => errors.push(error(&format!("unknown action `{}`", name))),
+                (_, None) => errors.push(error(&format!("unknown key `{}`", key))),
+            }
+        }
+
+        bindings.bindings.retain(|(_, action)| {
+            !configured.iter().any(|(_, configured)| configured == action)
+        });
+        bindings.bindings.extend(configured);
+
+        (bindings, errors)
+    }
+
+    pub fn action(&self, key_code: KeyCode, modifiers: Modifiers) -> Option<Action> {
+        self.bindings
+            .iter()
+            .filter(|(binding, _)| binding.matches(key_code, modifiers))
+            .max_by_key(|(binding, _)| binding.specificity())
+            .map(|(_, action)| *action)
+    }
+}
+
+fn key_code(name: &str) -> Option<KeyCode> {
+    const LETTERS: [KeyCode; 26] = [
+        KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F,
+        KeyCode::G, KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L,
+        KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R,
+        KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X,
+        KeyCode::Y, KeyCode::Z,
+    ];
+
+    const DIGITS: [KeyCode; 10] = [
+        KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+        KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    ];
+
+    const F_KEYS: [KeyCode; 12] = [
+        KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+        KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    ];
+
+    let mut chars = name.chars();
+
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphabetic() {
+            return Some(LETTERS[(c.to_ascii_uppercase() as u8 - b'A') as usize]);
+        }
+
+        if let Some(digit) = c.to_digit(10) {
+            return Some(DIGITS[digit as usize]);
+        }
+    }
+
+    if let Some(number) = name.strip_prefix('F').and_then(|number| number.parse::<usize>().ok()) {
+        return F_KEYS.get(number.checked_sub(1)?).copied();
+    }
+
+    let key_code = match name.to_lowercase().as_str() {
+        "space" => KeyCode::Space,
+        "enter" | "return" => KeyCode::Enter,
+        "escape" | "esc" => KeyCode::Escape,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "plus" => KeyCode::Plus,
+        "minus" | "-" => KeyCode::Minus,
+        "equals" | "=" => KeyCode::Equals,
+        "numpadadd" => KeyCode::NumpadAdd,
+        "numpadsubtract" => KeyCode::NumpadSubtract,
+        "comma" | "," => KeyCode::Comma,
+        "period" | "." => KeyCode::Period,
+        "slash" | "/" => KeyCode::Slash,
+        _ => return None,
+    };
+
+    Some(key_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: Modifiers = Modifiers { shift: false, control: false, alt: false, logo: false };
+    const CONTROL: Modifiers = Modifiers { control: true, ..NONE };
+
+    #[test]
+    fn parses_keys_and_modifiers() {
+        assert_eq!(Binding::parse("Space"), Some(Binding::key(KeyCode::Space)));
+        assert_eq!(Binding::parse("n"), Some(Binding::key(KeyCode::N)));
+        assert_eq!(Binding::parse("7"), Some(Binding::key(KeyCode::Key7)));
+        assert_eq!(Binding::parse("F12"), Some(Binding::key(KeyCode::F12)));
+        assert_eq!(Binding::parse("Ctrl+Z"), Some(Binding::control(KeyCode::Z, false)));
+        assert_eq!(Binding::parse("shift + control + z"), Some(Binding::control(KeyCode::Z, true)));
+
+        let binding = Binding::parse("Alt+Logo+Left").unwrap();
+
+        assert_eq!(binding.key_code, KeyCode::Left);
+        assert_eq!(binding.modifiers, Modifiers { alt: true, logo: true, ..NONE });
+    }
+
+    #[test]
+    fn parses_the_plus_key() {
+        assert_eq!(Binding::parse("+"), Some(Binding::key(KeyCode::Plus)));
+        assert_eq!(Binding::parse("Ctrl++"), Some(Binding::control(KeyCode::Plus, false)));
+        assert_eq!(Binding::parse("Plus"), Some(Binding::key(KeyCode::Plus)));
+    }
+
+    #[test]
+    fn rejects_bad_keys() {
+        for text in &["", "Ctrl+", "Ctrl++A", "Hyper+A", "F0", "F13", "Ctrl+Shift", "AB", "é"] {
+            assert_eq!(Binding::parse(text), None, "{:?}", text);
+        }
+
+        assert_eq!(Action::parse("tool_0"), None);
+        assert_eq!(Action::parse("tool_9"), None);
+        assert_eq!(Action::parse("jump"), None);
+        assert_eq!(Action::parse("tool_1"), Some(Action::Tool(Tool::ALL[0])));
+    }
+
+    #[test]
+    fn loads_a_file_over_the_defaults() {
+        let path = std::env::temp_dir().join(format!("life-bindings-{}.cfg", std::process::id()));
+
+        fs::write(&path, "# Keys\n\nundo = Ctrl+U\nzoom_in = Ctrl++\ntool_3 = R\njump = J\nstep = Hyper+N\nfit\nfit = Ctrl+\n").unwrap();
+
+        let (bindings, errors) = Bindings::load(&path);
+        let lines: Vec<&str> = errors.iter().map(|error| &error[path.display().to_string().len()..]).collect();
+
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(lines, vec![
+            ":6: unknown action `jump`",
+            ":7: unknown key `Hyper+N`",
+            ":8: expected `action = key`",
+            ":9: unknown key `Ctrl+`",
+        ]);
+
+        assert_eq!(bindings.action(KeyCode::U, CONTROL), Some(Action::Undo));
+        assert_eq!(bindings.action(KeyCode::Z, NONE), None);
+        assert_eq!(bindings.action(KeyCode::Z, Modifiers { shift: true, ..CONTROL }), Some(Action::Redo));
+        assert_eq!(bindings.action(KeyCode::Plus, CONTROL), Some(Action::ZoomIn));
+        assert_eq!(bindings.action(KeyCode::Equals, NONE), None);
+        assert_eq!(bindings.action(KeyCode::R, NONE), Some(Action::Tool(Tool::ALL[2])));
+        assert_eq!(bindings.action(KeyCode::N, NONE), Some(Action::Step));
+        assert_eq!(bindings.action(KeyCode::F, NONE), Some(Action::Fit));
+    }
+
+    #[test]
+    fn missing_files_give_the_defaults() {
+        let (bindings, errors) = Bindings::load(std::env::temp_dir().join("life-bindings-missing.cfg"));
+
+        assert!(errors.is_empty());
+        assert_eq!(bindings.action(KeyCode::Space, NONE), Some(Action::TogglePlayback));
+        assert_eq!(bindings.action(KeyCode::Plus, Modifiers { shift: true, ..NONE }), Some(Action::ZoomIn));
+    }
+}
@@ -0,0 +1,245 @@
+use iced::canvas::{self, Cache, Canvas, Cursor, Geometry};
+use iced::{
+    button, scrollable, Button, Column, Element, Length, Point, Rectangle, Row,
+    Scrollable, Size, Text, Color,
+};
+
+use crate::library::Library;
+use crate::preset::CATALOG;
+use crate::theme::Theme;
+use crate::{Cell, Message};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Source {
+    Preset(usize),
+    Library(usize),
+}
+
+pub struct Browser {
+    presets: Vec<Entry>,
+    library: Vec<Entry>,
+    failures: Vec<String>,
+    directory: String,
+    theme: Theme,
+    scroll: scrollable::State,
+}
+
+struct Entry {
+    source: Source,
+    name: String,
+    details: Vec<String>,
+    description: String,
+    thumbnail: Thumbnail,
+    load: button::State,
+    place: button::State,
+}
+
+struct Thumbnail {
+    cells: Vec<Cell>,
+    background: Color,
+    cell: Color,
+    cache: Cache,
+}
+
+impl Browser {
+    const THUMBNAIL_SIZE: u16 = 64;
+
+    pub fn new(library: &Library, theme: &Theme) -> Self {
+        let presets = CATALOG
+            .iter()
+            .enumerate()
+            .map(|(index, preset)| {
+                let mut details = vec![String::from(preset.category.name())];
+
+                if let Some(author) = preset.author {
+                    details.push(String::from(author));
+                }
+
+                if let Some(period) = preset.period {
+                    details.push(format!("p{}", period));
+                }
+
+                if let Some(speed) = preset.speed {
+                    details.push(String::from(speed));
+                }
+
+                Entry::new(
+                    Source::Preset(index),
+                    String::from(preset.name),
+                    details,
+                    String::from(preset.description),
+                    preset.cells(),
+                    theme,
+                )
+            })
+            .collect();
+
+        let mut browser = Browser {
+            presets,
+            library: Vec::new(),
+            failures: Vec::new(),
+            directory: String::new(),
+            theme: theme.clone(),
+            scroll: scrollable::State::new(),
+        };
+
+        browser.set_library(library);
+        browser
+    }
+
+    pub fn set_library(&mut self, library: &Library) {
+        self.directory = library.directory().display().to_string();
+
+        self.library = library
+            .entries()
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let mut details = Vec::new();
+
+                if let Some(author) = &entry.metadata.author {
+                    details.push(author.clone());
+                }
+
+                details.push(format!("{} células", entry.cells.len()));
+
+                Entry::new(
+                    Source::Library(index),
+                    entry.name.clone(),
+                    details,
+                    entry.metadata.comments.join(" "),
+                    entry.cells.clone(),
+                    &self.theme,
+                )
+            })
+            .collect();
+
+        self.failures = library
+            .failures()
+            .iter()
+            .map(|failure| format!("{}: {}", failure.path.display(), failure.message))
+            .collect();
+    }
+
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.theme = theme.clone();
+
+        for entry in self.presets.iter_mut().chain(self.library.iter_mut()) {
+            entry.thumbnail.background = theme.background;
+            entry.thumbnail.cell = theme.cell;
+            entry.thumbnail.cache.clear();
+        }
+    }
+
+    pub fn view(&mut self) -> Element<Message> {
+        let heading = |title: String| Text::new(title).size(22);
+        let is_library_empty = self.library.is_empty();
+
+        let list = self.presets.iter_mut().fold(
+            Scrollable::new(&mut self.scroll)
+                .width(Length::Units(360))
+                .height(Length::Fill)
+                .padding(10)
+                .spacing(15)
+                .push(heading(String::from("Catálogo"))),
+            |list, entry| list.push(entry.view()),
+        );
+
+        let list = self.library.iter_mut().fold(
+            list.push(heading(format!("Biblioteca ({})", self.directory))),
+            |list, entry| list.push(entry.view()),
+        );
+
+        let list = if is_library_empty {
+            list.push(Text::new("No hay patrones en la biblioteca.").size(14))
+        } else {
+            list
+        };
+
+        self.failures.iter().fold(
+            list,
+            |list, failure| list.push(
+                Text::new(format!("Error: {}", failure))
+                    .size(14)
+                    .color(Color::from_rgb(0.8, 0.2, 0.2))
+            ),
+        )
+        .into()
+    }
+}
+
+impl Entry {
+    fn new(source: Source, name: String, details: Vec<String>, description: String, cells: Vec<Cell>, theme: &Theme) -> Self {
+        Entry {
+            source,
+            name,
+            details,
+            description,
+            thumbnail: Thumbnail {
+                cells,
+                background: theme.background,
+                cell: theme.cell,
+                cache: Cache::default(),
+            },
+            load: button::State::new(),
+            place: button::State::new(),
+        }
+    }
+
+    fn view(&mut self) -> Element<Message> {
+        let thumbnail = Canvas::new(&mut self.thumbnail)
+            .width(Length::Units(Browser::THUMBNAIL_SIZE))
+            .height(Length::Units(Browser::THUMBNAIL_SIZE));
+
+        let buttons = Row::new()
+            .spacing(10)
+            .push(
+                Button::new(&mut self.load, Text::new("Cargar").size(14))
+                    .on_press(Message::LoadPattern(self.source))
+            )
+            .push(
+                Button::new(&mut self.place, Text::new("Colocar").size(14))
+                    .on_press(Message::PlacePattern(self.source))
+            );
+
+        let mut information = Column::new()
+            .spacing(5)
+            .push(Text::new(self.name.as_str()).size(18))
+            .push(Text::new(self.details.join(" · ")).size(14));
+
+        if !self.description.is_empty() {
+            information = information.push(Text::new(self.description.as_str()).size(14));
+        }
+
+        Row::new()
+            .spacing(10)
+            .push(thumbnail)
+            .push(information.push(buttons))
+            .into()
+    }
+}
+
+impl canvas::Program<Message> for Thumbnail {
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let thumbnail = self.cache.draw(bounds.size(), |frame| {
+            frame.fill_rectangle(Point::ORIGIN, frame.size(), self.background);
+
+            let rows = self.cells.iter().map(|cell| cell.i + 1).max().unwrap_or(1) as f32;
+            let columns = self.cells.iter().map(|cell| cell.j + 1).max().unwrap_or(1) as f32;
+
+            let size = (frame.width() / columns).min(frame.height() / rows);
+            let left = (frame.width() - columns * size) / 2.0;
+            let top = (frame.height() - rows * size) / 2.0;
+
+            for cell in &self.cells {
+                frame.fill_rectangle(
+                    Point::new(left + cell.j as f32 * size, top + cell.i as f32 * size),
+                    Size::new(size, size),
+                    self.cell,
+                );
+            }
+        });
+
+        vec![thumbnail]
+    }
+}
@@ -0,0 +1,143 @@
+use iced::{
+    button, text_input, Align, Button, Element, Length, Row, Text, TextInput,
+};
+
+use crate::session::Bookmark;
+use crate::{Cell, Message};
+
+#[derive(Default)]
+pub struct Panel {
+    fit: button::State,
+    coordinate: text_input::State,
+    coordinate_value: String,
+    go: button::State,
+    name: text_input::State,
+    name_value: String,
+    save: button::State,
+    unfollow: button::State,
+    bookmarks: Vec<(button::State, button::State)>,
+}
+
+impl Panel {
+    // Whether a text field has the keyboard, so bindings should not fire.
+    pub fn is_editing(&self) -> bool {
+        self.coordinate.is_focused() || self.name.is_focused()
+    }
+
+    pub fn set_coordinate(&mut self, value: String) {
+        self.coordinate_value = value;
+    }
+
+    pub fn set_name(&mut self, value: String) {
+        self.name_value = value;
+    }
+
+    pub fn coordinate(&self) -> Option<Cell> {
+        parse_coordinate(&self.coordinate_value)
+    }
+
+    // Takes the typed name, falling back to a numbered one.
+    pub fn take_name(&mut self, count: usize) -> String {
+        let name = std::mem::take(&mut self.name_value);
+
+        if name.trim().is_empty() {
+            format!("Marcador {}", count + 1)
+        } else {
+            String::from(name.trim())
+        }
+    }
+
+    pub fn view(&mut self, bookmarks: &[Bookmark], is_following: bool) -> Element<Message> {
+        self.bookmarks.resize_with(bookmarks.len(), Default::default);
+
+        let row = Row::new()
+            .padding(10)
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(
+                Button::new(&mut self.fit, Text::new("Encuadrar"))
+                    .on_press(Message::Fit)
+            )
+            .push(
+                TextInput::new(&mut self.coordinate, "x, y", &self.coordinate_value, Message::CoordinateChanged)
+                    .on_submit(Message::GoTo)
+                    .padding(5)
+                    .width(Length::Units(100))
+            )
+            .push(
+                Button::new(&mut self.go, Text::new("Ir"))
+                    .on_press(Message::GoTo)
+            )
+            .push(
+                TextInput::new(&mut self.name, "Nombre del marcador", &self.name_value, Message::BookmarkNameChanged)
+                    .on_submit(Message::AddBookmark)
+                    .padding(5)
+                    .width(Length::Units(160))
+            )
+            .push(
+                Button::new(&mut self.save, Text::new("Guardar vista"))
+                    .on_press(Message::AddBookmark)
+            );
+
+        let row = if is_following {
+            row.push(
+                Button::new(&mut self.unfollow, Text::new("Dejar de seguir"))
+                    .on_press(Message::Unfollow)
+            )
+        } else {
+            row
+        };
+
+        self.bookmarks
+            .iter_mut()
+            .zip(bookmarks)
+            .enumerate()
+            .fold(row, |row, (index, ((show, remove), bookmark))| {
+                row.push(
+                    Row::new()
+                        .push(
+                            Button::new(show, Text::new(bookmark.name.as_str()).size(16))
+                                .on_press(Message::ShowBookmark(index))
+                        )
+                        .push(
+                            Button::new(remove, Text::new("×").size(16))
+                                .on_press(Message::RemoveBookmark(index))
+                        )
+                )
+            })
+            .into()
+    }
+}
+
+// Reads `x, y` (column, row), separated by a comma or spaces.
+pub fn parse_coordinate(text: &str) -> Option<Cell> {
+    let mut values = text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|value| !value.is_empty())
+        .map(|value| value.parse::<isize>());
+
+    match (values.next(), values.next(), values.next()) {
+        (Some(Ok(j)), Some(Ok(i)), None) => Some(Cell { i, j }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_coordinates() {
+        assert_eq!(parse_coordinate("3, 7"), Some(Cell { i: 7, j: 3 }));
+        assert_eq!(parse_coordinate("-3,-7"), Some(Cell { i: -7, j: -3 }));
+        assert_eq!(parse_coordinate("  -120   45 "), Some(Cell { i: 45, j: -120 }));
+        assert_eq!(parse_coordinate("+2,,-0"), Some(Cell { i: 0, j: 2 }));
+    }
+
+    #[test]
+    fn rejects_malformed_coordinates() {
+        for text in &["", "3", "3,", "3, 4, 5", "x, 4", "--3, 4", "3.5, 4", "3 - 4", "99999999999999999999, 0"] {
+            assert_eq!(parse_coordinate(text), None, "{:?}", text);
+        }
+    }
+}
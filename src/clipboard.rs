@@ -0,0 +1,84 @@
+use clipboard::{ClipboardContext, ClipboardProvider};
+
+pub trait Clipboard {
+    fn read(&mut self) -> Option<String>;
+
+    fn write(&mut self, contents: String);
+}
+
+pub struct System {
+    context: ClipboardContext,
+}
+
+impl Clipboard for System {
+    fn read(&mut self) -> Option<String> {
+        self.context.get_contents().ok()
+    }
+
+    fn write(&mut self, contents: String) {
+        if let Err(error) = self.context.set_contents(contents) {
+            eprintln!("Could not write to the clipboard: {}", error);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Memory {
+    contents: Option<String>,
+}
+
+impl Clipboard for Memory {
+    fn read(&mut self) -> Option<String> {
+        self.contents.clone()
+    }
+
+    fn write(&mut self, contents: String) {
+        self.contents = Some(contents);
+    }
+}
+
+// Falls back to an in-memory clipboard when the system one is unavailable,
+// e.g. when running without a display server.
+pub fn system() -> Box<dyn Clipboard> {
+    match ClipboardContext::new() {
+        Ok(context) => Box::new(System { context }),
+        Err(_) => Box::new(Memory::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::pattern;
+    use crate::selection::Selection;
+    use crate::{Cell, Grid, Life};
+
+    const GLIDER: [(isize, isize); 5] = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+
+    #[test]
+    fn pastes_a_copied_selection() {
+        let life: Life = GLIDER
+            .iter()
+            .map(|&(i, j)| Cell { i: i + 10, j: j + 20 })
+            .chain(std::iter::once(Cell { i: 0, j: 0 }))
+            .collect();
+
+        let selection = Selection::new(Cell { i: 14, j: 24 }, Cell { i: 10, j: 20 });
+        let mut clipboard = Memory::default();
+
+        clipboard.write(pattern::to_rle(&selection.cells(&life)));
+
+        let cells = pattern::parse(&clipboard.read().unwrap()).unwrap();
+        let mut grid = Grid::from_cells(Vec::new());
+
+        grid.start_paste(cells);
+        grid.place(Cell { i: -7, j: 3 });
+
+        let pasted: HashSet<Cell> = GLIDER.iter().map(|&(i, j)| Cell { i: i - 7, j: j + 3 }).collect();
+
+        assert_eq!(grid.life.cells, pasted);
+        assert!(!grid.is_pasting());
+    }
+}
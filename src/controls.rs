@@ -0,0 +1,86 @@
+use iced::{button, slider, Align, Button, Element, Row, Slider, Text};
+
+use crate::Message;
+
+#[derive(Default)]
+pub struct Controls {
+    toggle: button::State,
+    step: button::State,
+    reset: button::State,
+    clear: button::State,
+    speed: slider::State,
+    generations_per_frame: slider::State,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Playback {
+    pub is_playing: bool,
+    pub speed: u16,
+    pub generations_per_frame: u16,
+}
+
+impl Playback {
+    pub const MAX_SPEED: u16 = 60;
+    pub const MAX_GENERATIONS_PER_FRAME: u16 = 100;
+
+    pub fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(1000 / self.speed.max(1) as u64)
+    }
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Playback {
+            is_playing: true,
+            speed: 20,
+            generations_per_frame: 1,
+        }
+    }
+}
+
+impl Controls {
+    pub fn view(&mut self, playback: Playback, generation: usize) -> Element<Message> {
+        let toggle = Button::new(
+            &mut self.toggle,
+            Text::new(if playback.is_playing { "Pausa" } else { "Reproducir" }),
+        )
+        .on_press(Message::TogglePlayback);
+
+        let mut step = Button::new(&mut self.step, Text::new("Paso"));
+
+        if !playback.is_playing {
+            step = step.on_press(Message::Step);
+        }
+
+        Row::new()
+            .padding(10)
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(toggle)
+            .push(step)
+            .push(
+                Button::new(&mut self.reset, Text::new("Reiniciar"))
+                    .on_press(Message::Reset)
+            )
+            .push(
+                Button::new(&mut self.clear, Text::new("Vaciar"))
+                    .on_press(Message::Clear)
+            )
+            .push(Slider::new(
+                &mut self.speed,
+                1..=Playback::MAX_SPEED,
+                playback.speed,
+                Message::SpeedChanged,
+            ))
+            .push(Text::new(format!("{} gen/s", playback.speed)).size(16))
+            .push(Slider::new(
+                &mut self.generations_per_frame,
+                1..=Playback::MAX_GENERATIONS_PER_FRAME,
+                playback.generations_per_frame,
+                Message::GenerationsPerFrameChanged,
+            ))
+            .push(Text::new(format!("{} gen/paso", playback.generations_per_frame)).size(16))
+            .push(Text::new(format!("Generación {}", generation)).size(16))
+            .into()
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use crate::{Cell, Life};
+
+// Live cell counts over square blocks of 2^level cells per side, for drawing
+// the universe when a cell is smaller than a pixel. Each level is summed
+// from the one below it, so the population is only visited once.
+pub struct Summary {
+    levels: Vec<HashMap<Cell, u64>>,
+}
+
+impl Summary {
+    pub const MAX_LEVEL: usize = 40;
+
+    pub fn new(life: &Life, level: usize) -> Summary {
+        let level = level.clamp(1, Self::MAX_LEVEL);
+        let mut levels: Vec<HashMap<Cell, u64>> = Vec::with_capacity(level);
+
+        let mut blocks = HashMap::new();
+
+        for cell in &life.cells {
+            *blocks.entry(Self::block(*cell, 1)).or_insert(0) += 1;
+        }
+
+        levels.push(blocks);
+
+        while levels.len() < level {
+            let mut blocks = HashMap::new();
+
+            for (block, count) in &levels[levels.len() - 1] {
+                *blocks.entry(Self::block(*block, 1)).or_insert(0) += count;
+            }
+
+            levels.push(blocks);
+        }
+
+        Summary { levels }
+    }
+
+    pub fn level(&self) -> usize {
+        self.levels.len()
+    }
+
+    // The blocks of the top level with the fraction of their cells that are
+    // alive.
+    pub fn blocks(&self) -> impl Iterator<Item = (Cell, f32)> + '_ {
+        let level = self.level();
+        let capacity = (1u64 << level) as f32 * (1u64 << level) as f32;
+
+        self.levels[level - 1]
+            .iter()
+            .map(move |(block, count)| (*block, *count as f32 / capacity))
+    }
+
+    // The block at `level` holding the cell; shifting rounds toward negative
+    // infinity, so blocks tile the negative quadrants too.
+    fn block(cell: Cell, level: usize) -> Cell {
+        Cell {
+            i: cell.i >> level,
+            j: cell.j >> level,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(cells: &[(isize, isize)]) -> Life {
+        cells.iter().map(|&(i, j)| Cell { i, j }).collect()
+    }
+
+    fn counts(summary: &Summary) -> HashMap<(isize, isize), f32> {
+        summary.blocks().map(|(block, fraction)| ((block.i, block.j), fraction)).collect()
+    }
+
+    #[test]
+    fn blocks_straddling_the_origin_round_down() {
+        let life = life(&[(-1, -1), (0, 0), (-1, 0), (0, -1), (-2, -2), (1, 1), (-3, 2)]);
+
+        let expected: HashMap<(isize, isize), f32> = [
+            ((-1, -1), 2.0),
+            ((0, 0), 2.0),
+            ((-1, 0), 1.0),
+            ((0, -1), 1.0),
+            ((-2, 1), 1.0),
+        ]
+        .iter()
+        .map(|&(block, count)| (block, count / 4.0))
+        .collect();
+
+        assert_eq!(counts(&Summary::new(&life, 1)), expected);
+
+        let expected: HashMap<(isize, isize), f32> = [
+            ((-1, -1), 2.0),
+            ((0, 0), 2.0),
+            ((-1, 0), 2.0),
+            ((0, -1), 1.0),
+        ]
+        .iter()
+        .map(|&(block, count)| (block, count / 16.0))
+        .collect();
+
+        assert_eq!(counts(&Summary::new(&life, 2)), expected);
+    }
+
+    #[test]
+    fn levels_match_blocks_taken_directly() {
+        let cells: Vec<(isize, isize)> = (-40..40).map(|k| (k * 7 % 23 - 11, k * 13 % 37 - 18)).collect();
+        let life = life(&cells);
+
+        for level in 1..=6 {
+            let mut expected: HashMap<(isize, isize), f32> = HashMap::new();
+
+            for cell in &life.cells {
+                let size = (1 << level) as f32;
+
+                *expected.entry((cell.i.div_euclid(1 << level), cell.j.div_euclid(1 << level))).or_insert(0.0) += 1.0 / (size * size);
+            }
+
+            assert_eq!(counts(&Summary::new(&life, level)), expected, "level {}", level);
+        }
+
+        assert_eq!(Summary::new(&life, 0).level(), 1);
+        assert_eq!(Summary::new(&life, 100).level(), Summary::MAX_LEVEL);
+    }
+}
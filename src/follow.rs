@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+
+use iced::Point;
+
+use crate::selection::Selection;
+use crate::{Cell, Life};
+
+// Follows an object from generation to generation. The object is the group
+// of live cells connected within `REACH` of each other, which keeps the
+// separate sparks of a spaceship together.
+#[derive(Debug, Clone)]
+pub struct Tracker {
+    cells: Vec<Cell>,
+}
+
+impl Tracker {
+    const REACH: isize = 2;
+    const MAX_CELLS: usize = 10_000;
+
+    // Picks the object at or next to the given cell.
+    pub fn pick(life: &Life, cell: Cell) -> Option<Tracker> {
+        let seeds = Self::live_cells(life, Selection::new(cell, cell), Self::REACH);
+
+        Self::component(life, seeds).map(|cells| Tracker { cells })
+    }
+
+    // Finds the object again after one generation; it can have moved at most
+    // one cell. Returns false once the object is gone or has merged into
+    // something too large to follow.
+    pub fn advance(&mut self, life: &Life) -> bool {
+        let seeds = Self::live_cells(life, self.bounds(), Self::REACH);
+
+        match Self::component(life, seeds) {
+            Some(cells) => {
+                self.cells = cells;
+                true
+            },
+            None => false,
+        }
+    }
+
+    // The center of mass, in cell units with x as the column.
+    pub fn center(&self) -> Point {
+        let count = self.cells.len().max(1) as f32;
+
+        let (x, y) = self.cells.iter().fold((0.0, 0.0), |(x, y), cell| {
+            (x + cell.j as f32 + 0.5, y + cell.i as f32 + 0.5)
+        });
+
+        Point::new(x / count, y / count)
+    }
+
+    pub fn bounds(&self) -> Selection {
+        let top = self.cells.iter().map(|cell| cell.i).min().unwrap_or(0);
+        let bottom = self.cells.iter().map(|cell| cell.i).max().unwrap_or(0);
+        let left = self.cells.iter().map(|cell| cell.j).min().unwrap_or(0);
+        let right = self.cells.iter().map(|cell| cell.j).max().unwrap_or(0);
+
+        Selection::new(Cell { i: top, j: left }, Cell { i: bottom, j: right })
+    }
+
+    fn live_cells(life: &Life, bounds: Selection, margin: isize) -> Vec<Cell> {
+        let mut cells = Vec::new();
+
+        for i in bounds.top_left.i - margin..=bounds.bottom_right.i + margin {
+            for j in bounds.top_left.j - margin..=bounds.bottom_right.j + margin {
+                if life.contains(&Cell { i, j }) {
+                    cells.push(Cell { i, j });
+                }
+            }
+        }
+
+        cells
+    }
+
+    fn component(life: &Life, seeds: Vec<Cell>) -> Option<Vec<Cell>> {
+        let mut visited: HashSet<Cell> = seeds.iter().copied().collect();
+        let mut pending = seeds;
+
+        while let Some(cell) = pending.pop() {
+            if visited.len() > Self::MAX_CELLS {
+                return None;
+            }
+
+            for neighbor in Self::live_cells(life, Selection::new(cell, cell), Self::REACH) {
+                if visited.insert(neighbor) {
+                    pending.push(neighbor);
+                }
+            }
+        }
+
+        if visited.is_empty() {
+            None
+        } else {
+            Some(visited.into_iter().collect())
+        }
+    }
+}
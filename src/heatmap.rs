@@ -0,0 +1,226 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+
+use iced::{button, slider, Align, Button, Color, Element, Row, Slider, Text};
+
+use crate::selection::Selection;
+use crate::{Cell, Message};
+
+// How many times each cell changed state over the last `length` generations,
+// or since the window was reset if that is more recent.
+#[derive(Debug, Clone)]
+pub struct Heatmap {
+    counts: HashMap<Cell, u32>,
+    // The cells that changed in each generation of the window, oldest first.
+    window: VecDeque<Vec<Cell>>,
+    changing: Vec<Cell>,
+    length: usize,
+    start: usize,
+}
+
+impl Default for Heatmap {
+    fn default() -> Self {
+        Heatmap {
+            counts: HashMap::new(),
+            window: VecDeque::new(),
+            changing: Vec::new(),
+            length: 100,
+            start: 0,
+        }
+    }
+}
+
+impl Heatmap {
+    pub const LEVELS: usize = 8;
+    pub const MIN_LENGTH: u16 = 1;
+    pub const MAX_LENGTH: u16 = 1000;
+
+    pub fn reset(&mut self, generation: usize) {
+        self.counts.clear();
+        self.window.clear();
+        self.changing.clear();
+        self.start = generation;
+    }
+
+    pub fn changed(&mut self, cell: Cell) {
+        *self.counts.entry(cell).or_insert(0) += 1;
+        self.changing.push(cell);
+    }
+
+    pub fn advance(&mut self) {
+        let changed = std::mem::take(&mut self.changing);
+
+        self.window.push_back(changed);
+        self.slide();
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn set_length(&mut self, length: usize) {
+        self.length = length;
+        self.slide();
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn generations(&self) -> usize {
+        self.window.len()
+    }
+
+    // Forgets the generations that fell out of the window.
+    fn slide(&mut self) {
+        while self.window.len() > self.length {
+            for cell in self.window.pop_front().unwrap_or_default() {
+                if let Some(count) = self.counts.get_mut(&cell) {
+                    *count -= 1;
+
+                    if *count == 0 {
+                        self.counts.remove(&cell);
+                    }
+                }
+            }
+
+            self.start += 1;
+        }
+    }
+
+    // The visible counted cells grouped into `LEVELS` levels of activity,
+    // relative to the busiest cell anywhere.
+    pub fn visible(&self, area: &Selection) -> Vec<(usize, Vec<Cell>)> {
+        let max = self.counts.values().copied().max().unwrap_or(0).max(1) as f32;
+        let mut levels: Vec<Vec<Cell>> = vec![Vec::new(); Self::LEVELS];
+
+        for (cell, count) in &self.counts {
+            if area.contains(cell) {
+                let level = (*count as f32 / max * Self::LEVELS as f32).ceil() as usize;
+
+                levels[level.clamp(1, Self::LEVELS) - 1].push(*cell);
+            }
+        }
+
+        levels.into_iter()
+            .enumerate()
+            .filter(|(_, cells)| !cells.is_empty())
+            .collect()
+    }
+
+    // One `i,j,changes` row per cell that changed, by row and column.
+    pub fn to_csv(&self) -> String {
+        let mut counts: Vec<(&Cell, &u32)> = self.counts.iter().collect();
+        counts.sort_by_key(|(cell, _)| (cell.i, cell.j));
+
+        let mut csv = String::from("i,j,changes\n");
+
+        for (cell, count) in counts {
+            let _ = writeln!(csv, "{},{},{}", cell.i, cell.j, count);
+        }
+
+        csv
+    }
+}
+
+// Interpolates between the low, middle and high colors of a ramp.
+pub fn color(ramp: &[Color; 3], level: usize) -> Color {
+    let t = (level + 1) as f32 / Heatmap::LEVELS as f32;
+
+    let (from, to, t) = if t < 0.5 {
+        (ramp[0], ramp[1], t * 2.0)
+    } else {
+        (ramp[1], ramp[2], t * 2.0 - 1.0)
+    };
+
+    Color {
+        r: from.r + (to.r - from.r) * t,
+        g: from.g + (to.g - from.g) * t,
+        b: from.b + (to.b - from.b) * t,
+        a: from.a + (to.a - from.a) * t,
+    }
+}
+
+#[derive(Default)]
+pub struct Bar {
+    toggle: button::State,
+    length: slider::State,
+    reset: button::State,
+    export: button::State,
+}
+
+impl Bar {
+    pub fn view(&mut self, is_enabled: bool, length: usize, start: usize, generations: usize) -> Element<Message> {
+        let toggle = Button::new(
+            &mut self.toggle,
+            Text::new(if is_enabled { "Ocultar actividad" } else { "Mostrar actividad" }),
+        )
+        .on_press(Message::ToggleHeatmap);
+
+        let mut row = Row::new()
+            .padding(10)
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(toggle);
+
+        if is_enabled {
+            row = row
+                .push(Slider::new(
+                    &mut self.length,
+                    Heatmap::MIN_LENGTH..=Heatmap::MAX_LENGTH,
+                    length as u16,
+                    Message::HeatmapLengthChanged,
+                ))
+                .push(Text::new(format!("Ventana de {} gen", length)).size(16))
+                .push(
+                    Button::new(&mut self.reset, Text::new("Reiniciar ventana"))
+                        .on_press(Message::ResetHeatmap)
+                )
+                .push(
+                    Button::new(&mut self.export, Text::new("Exportar CSV"))
+                        .on_press(Message::ExportHeatmap)
+                )
+                .push(Text::new(format!("{} generaciones desde la {}", generations, start)).size(16));
+        }
+
+        row.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_over_a_sliding_window() {
+        let mut heatmap = Heatmap::default();
+        let a = Cell { i: 0, j: 0 };
+        let b = Cell { i: 1, j: 1 };
+
+        heatmap.set_length(2);
+
+        heatmap.changed(a);
+        heatmap.advance();
+
+        heatmap.changed(a);
+        heatmap.changed(b);
+        heatmap.advance();
+
+        assert_eq!(heatmap.counts[&a], 2);
+        assert_eq!(heatmap.start(), 0);
+
+        heatmap.changed(b);
+        heatmap.advance();
+
+        assert_eq!(heatmap.counts[&a], 1);
+        assert_eq!(heatmap.counts[&b], 2);
+        assert_eq!(heatmap.start(), 1);
+        assert_eq!(heatmap.generations(), 2);
+
+        heatmap.set_length(1);
+
+        assert!(!heatmap.counts.contains_key(&a));
+        assert_eq!(heatmap.counts[&b], 1);
+        assert_eq!(heatmap.to_csv(), "i,j,changes\n1,1,1\n");
+    }
+}
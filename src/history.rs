@@ -0,0 +1,341 @@
+use std::collections::HashSet;
+
+use crate::selection::Selection;
+use crate::{Cell, Life};
+
+#[derive(Debug, Clone, Default)]
+struct Change {
+    born: HashSet<Cell>,
+    died: HashSet<Cell>,
+    generation: Option<(usize, usize)>,
+    // Only kept along with changed cells, so selecting alone is not a step.
+    selection: Option<(Option<Selection>, Option<Selection>)>,
+}
+
+impl Change {
+    fn is_empty(&self) -> bool {
+        self.born.is_empty() && self.died.is_empty() && self.generation.is_none()
+    }
+}
+
+#[derive(Clone)]
+enum Step {
+    Edit(Change),
+    // The universe before the simulation ran; undoing swaps it back in.
+    Advance { life: Life, generation: usize },
+}
+
+// Undo and redo stacks of edits. Edits made while a group is open, such as
+// the cells of a single stroke, become one step. Consecutive generations
+// collapse into a single "advance" step.
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    group: Option<Change>,
+    depth: usize,
+    stroke: bool,
+}
+
+impl History {
+    const MAX_STEPS: usize = 100;
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn begin(&mut self) {
+        self.depth += 1;
+        self.group.get_or_insert_with(Change::default);
+    }
+
+    pub fn end(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+        self.close();
+    }
+
+    // A stroke stays open across messages until the mouse button is released.
+    pub fn begin_stroke(&mut self) {
+        self.stroke = true;
+        self.group.get_or_insert_with(Change::default);
+    }
+
+    pub fn end_stroke(&mut self) {
+        self.stroke = false;
+        self.close();
+    }
+
+    pub fn born(&mut self, cell: Cell) {
+        self.begin();
+
+        if let Some(group) = &mut self.group {
+            if !group.died.remove(&cell) {
+                group.born.insert(cell);
+            }
+        }
+
+        self.end();
+    }
+
+    pub fn died(&mut self, cell: Cell) {
+        self.begin();
+
+        if let Some(group) = &mut self.group {
+            if !group.born.remove(&cell) {
+                group.died.insert(cell);
+            }
+        }
+
+        self.end();
+    }
+
+    pub fn diff(&mut self, before: &Life, after: &Life) {
+        self.begin();
+
+        for cell in after.cells.difference(&before.cells) {
+            self.born(*cell);
+        }
+
+        for cell in before.cells.difference(&after.cells) {
+            self.died(*cell);
+        }
+
+        self.end();
+    }
+
+    pub fn generation(&mut self, before: usize, after: usize) {
+        if before == after {
+            return;
+        }
+
+        self.begin();
+
+        if let Some(group) = &mut self.group {
+            let first = group.generation.map_or(before, |(first, _)| first);
+
+            group.generation = Some((first, after));
+        }
+
+        self.end();
+    }
+
+    pub fn selection(&mut self, before: Option<Selection>, after: Option<Selection>) {
+        if before == after {
+            return;
+        }
+
+        self.begin();
+
+        if let Some(group) = &mut self.group {
+            let first = group.selection.map_or(before, |(first, _)| first);
+
+            group.selection = Some((first, after));
+        }
+
+        self.end();
+    }
+
+    // Called before the simulation advances from `life`. A group still open,
+    // like a stroke drawn while playing, is closed first so its cells are
+    // undone against the universe they were drawn on; the stroke goes on in
+    // a new group.
+    pub fn advance(&mut self, life: &Life, generation: usize) {
+        if let Some(change) = self.group.take() {
+            if !change.is_empty() {
+                self.push(Step::Edit(change));
+            }
+
+            if self.depth > 0 || self.stroke {
+                self.group = Some(Change::default());
+            }
+        }
+
+        if let Some(Step::Advance { .. }) = self.undo.last() {
+            if self.redo.is_empty() {
+                return;
+            }
+        }
+
+        self.push(Step::Advance { life: life.clone(), generation });
+    }
+
+    pub fn undo(&mut self, life: &mut Life, generation: &mut usize, selection: &mut Option<Selection>) -> bool {
+        match self.undo.pop() {
+            Some(step) => {
+                let step = Self::revert(step, life, generation, selection);
+
+                self.redo.push(step);
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self, life: &mut Life, generation: &mut usize, selection: &mut Option<Selection>) -> bool {
+        match self.redo.pop() {
+            Some(step) => {
+                let step = Self::apply(step, life, generation, selection);
+
+                self.undo.push(step);
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn revert(step: Step, life: &mut Life, generation: &mut usize, selection: &mut Option<Selection>) -> Step {
+        match step {
+            Step::Edit(change) => {
+                for cell in &change.born {
+                    life.unpopulate(cell);
+                }
+
+                for cell in &change.died {
+                    life.populate(*cell);
+                }
+
+                if let Some((before, _)) = change.generation {
+                    *generation = before;
+                }
+
+                if let Some((before, _)) = change.selection {
+                    *selection = before;
+                }
+
+                Step::Edit(change)
+            },
+            Step::Advance { life: other, generation: other_generation } => {
+                Self::swap(life, generation, other, other_generation)
+            },
+        }
+    }
+
+    fn apply(step: Step, life: &mut Life, generation: &mut usize, selection: &mut Option<Selection>) -> Step {
+        match step {
+            Step::Edit(change) => {
+                for cell in &change.died {
+                    life.unpopulate(cell);
+                }
+
+                for cell in &change.born {
+                    life.populate(*cell);
+                }
+
+                if let Some((_, after)) = change.generation {
+                    *generation = after;
+                }
+
+                if let Some((_, after)) = change.selection {
+                    *selection = after;
+                }
+
+                Step::Edit(change)
+            },
+            Step::Advance { life: other, generation: other_generation } => {
+                Self::swap(life, generation, other, other_generation)
+            },
+        }
+    }
+
+    fn swap(life: &mut Life, generation: &mut usize, other: Life, other_generation: usize) -> Step {
+        Step::Advance {
+            life: std::mem::replace(life, other),
+            generation: std::mem::replace(generation, other_generation),
+        }
+    }
+
+    fn close(&mut self) {
+        if self.depth > 0 || self.stroke {
+            return;
+        }
+
+        if let Some(change) = self.group.take() {
+            if !change.is_empty() {
+                self.push(Step::Edit(change));
+            }
+        }
+    }
+
+    fn push(&mut self, step: Step) {
+        self.redo.clear();
+        self.undo.push(step);
+
+        if self.undo.len() > Self::MAX_STEPS {
+            self.undo.remove(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(cells: &[(isize, isize)]) -> Life {
+        cells.iter().map(|&(i, j)| Cell { i, j }).collect()
+    }
+
+    #[test]
+    fn undoes_a_stroke_drawn_while_playing() {
+        let mut history = History::default();
+        let mut universe = life(&[(0, 0), (0, 1), (0, 2)]);
+        let mut generation = 0;
+        let mut selection = None;
+
+        history.advance(&universe, generation);
+        universe.tick();
+        generation += 1;
+
+        // The stroke goes on while the blinker keeps turning.
+        history.begin_stroke();
+        history.born(Cell { i: 5, j: 5 });
+        universe.populate(Cell { i: 5, j: 5 });
+
+        let drawn = universe.clone();
+
+        history.advance(&universe, generation);
+        universe.tick();
+        generation += 1;
+
+        history.born(Cell { i: 9, j: 9 });
+        universe.populate(Cell { i: 9, j: 9 });
+        history.end_stroke();
+
+        assert!(history.undo(&mut universe, &mut generation, &mut selection));
+        assert!(!universe.contains(&Cell { i: 9, j: 9 }));
+
+        assert!(history.undo(&mut universe, &mut generation, &mut selection));
+        assert_eq!(universe.cells, drawn.cells);
+        assert_eq!(generation, 1);
+
+        assert!(history.undo(&mut universe, &mut generation, &mut selection));
+        assert_eq!(universe.cells, life(&[(-1, 1), (0, 1), (1, 1)]).cells);
+    }
+
+    #[test]
+    fn restores_the_selection() {
+        let mut history = History::default();
+        let mut universe = life(&[(0, 0), (0, 1)]);
+        let mut generation = 0;
+        let before = Some(Selection::new(Cell { i: 0, j: 0 }, Cell { i: 0, j: 1 }));
+        let after = Some(Selection::new(Cell { i: 0, j: 0 }, Cell { i: 1, j: 0 }));
+        let mut selection = after;
+
+        history.begin();
+        history.diff(&universe, &life(&[(0, 0), (1, 0)]));
+        history.selection(before, after);
+        history.end();
+
+        universe = life(&[(0, 0), (1, 0)]);
+
+        assert!(history.undo(&mut universe, &mut generation, &mut selection));
+        assert_eq!(selection, before);
+        assert_eq!(universe.cells, life(&[(0, 0), (0, 1)]).cells);
+
+        assert!(history.redo(&mut universe, &mut generation, &mut selection));
+        assert_eq!(selection, after);
+    }
+}
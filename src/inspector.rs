@@ -0,0 +1,201 @@
+use std::collections::{HashMap, HashSet};
+
+use iced::{button, Align, Button, Element, Row, Text};
+
+use crate::selection::Selection;
+use crate::{Cell, Life, Message};
+
+// What becomes of a cell in the next generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fate {
+    Survives,
+    Dies,
+    Born,
+    StaysDead,
+}
+
+impl Fate {
+    pub fn of(is_alive: bool, neighbors: usize) -> Fate {
+        match (is_alive, Life::is_alive_next(is_alive, neighbors)) {
+            (true, true) => Fate::Survives,
+            (true, false) => Fate::Dies,
+            (false, true) => Fate::Born,
+            (false, false) => Fate::StaysDead,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Fate::Survives => "sobrevive",
+            Fate::Dies => "muere",
+            Fate::Born => "nace",
+            Fate::StaysDead => "sigue muerta",
+        }
+    }
+}
+
+// The visible cells that survive, die or are born, in that order. Only the
+// live cells in and next to `area`, and their neighbors, are looked at.
+pub fn fates(life: &Life, area: &Selection) -> [Vec<Cell>; 3] {
+    let around = Selection::new(
+        Cell { i: area.top_left.i.saturating_sub(1), j: area.top_left.j.saturating_sub(1) },
+        Cell { i: area.bottom_right.i.saturating_add(1), j: area.bottom_right.j.saturating_add(1) },
+    );
+
+    let candidates: HashSet<Cell> = life
+        .within(&around)
+        .into_iter()
+        .flat_map(Cell::cluster)
+        .filter(|cell| area.contains(cell))
+        .collect();
+
+    let mut fates = [Vec::new(), Vec::new(), Vec::new()];
+
+    for cell in candidates {
+        match Fate::of(life.contains(&cell), life.neighbors_of(cell)) {
+            Fate::Survives => fates[0].push(cell),
+            Fate::Dies => fates[1].push(cell),
+            Fate::Born => fates[2].push(cell),
+            Fate::StaysDead => {},
+        }
+    }
+
+    fates
+}
+
+// The generation in which each live cell was born or drawn.
+#[derive(Debug, Clone, Default)]
+pub struct Ages {
+    born: HashMap<Cell, usize>,
+}
+
+impl Ages {
+    pub fn changed(&mut self, cell: Cell, is_alive: bool, generation: usize) {
+        if is_alive {
+            self.born.insert(cell, generation);
+        } else {
+            self.born.remove(&cell);
+        }
+    }
+
+    // Catches up with edits that replace many cells at once. Cells that
+    // appeared are counted from `generation`.
+    pub fn sync(&mut self, life: &Life, generation: usize) {
+        self.born.retain(|cell, born| *born <= generation && life.contains(cell));
+
+        for cell in &life.cells {
+            self.born.entry(*cell).or_insert(generation);
+        }
+    }
+
+    pub fn age(&self, cell: &Cell, generation: usize) -> Option<usize> {
+        self.born.get(cell).map(|born| generation.saturating_sub(*born))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Inspection {
+    pub cell: Cell,
+    pub is_alive: bool,
+    pub neighbors: usize,
+    pub age: Option<usize>,
+    pub fate: Fate,
+}
+
+impl Inspection {
+    pub fn new(life: &Life, ages: &Ages, cell: Cell, generation: usize) -> Self {
+        let is_alive = life.contains(&cell);
+        let neighbors = life.neighbors_of(cell);
+
+        Inspection {
+            cell,
+            is_alive,
+            neighbors,
+            age: if is_alive { ages.age(&cell, generation) } else { None },
+            fate: Fate::of(is_alive, neighbors),
+        }
+    }
+
+    fn describe(&self) -> String {
+        let state = match self.age {
+            Some(age) => format!("viva desde hace {} gen", age),
+            None if self.is_alive => String::from("viva"),
+            None => String::from("muerta"),
+        };
+
+        format!(
+            "Célula ({}, {}): {}, {} vecinas vivas → {}",
+            self.cell.i,
+            self.cell.j,
+            state,
+            self.neighbors,
+            self.fate.name(),
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct Bar {
+    toggle: button::State,
+}
+
+impl Bar {
+    pub fn view(&mut self, show_fates: bool, inspection: Option<Inspection>) -> Element<Message> {
+        let toggle = Button::new(
+            &mut self.toggle,
+            Text::new(if show_fates { "Ocultar destino" } else { "Mostrar destino" }),
+        )
+        .on_press(Message::ToggleFates);
+
+        let description = match inspection {
+            Some(inspection) => inspection.describe(),
+            None => String::from("Con \"Inspeccionar\", haz clic en una célula para ver su estado"),
+        };
+
+        Row::new()
+            .padding(10)
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(toggle)
+            .push(Text::new(description).size(16))
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_the_rule() {
+        for neighbors in 0..=8 {
+            let alive = match neighbors {
+                2 | 3 => Fate::Survives,
+                _ => Fate::Dies,
+            };
+            let dead = match neighbors {
+                3 => Fate::Born,
+                _ => Fate::StaysDead,
+            };
+
+            assert_eq!(Fate::of(true, neighbors), alive, "{} neighbors", neighbors);
+            assert_eq!(Fate::of(false, neighbors), dead, "{} neighbors", neighbors);
+        }
+    }
+
+    #[test]
+    fn finds_the_fates_inside_an_area() {
+        let blinker: Life = (0..3).map(|j| Cell { i: 0, j }).collect();
+        let area = Selection::new(Cell { i: -1, j: 1 }, Cell { i: 1, j: 2 });
+
+        let [survives, dies, born] = fates(&blinker, &area);
+
+        assert_eq!(survives, vec![Cell { i: 0, j: 1 }]);
+        assert_eq!(dies, vec![Cell { i: 0, j: 2 }]);
+
+        let mut born = born;
+        born.sort_by_key(|cell| cell.i);
+
+        assert_eq!(born, vec![Cell { i: -1, j: 1 }, Cell { i: 1, j: 1 }]);
+    }
+}
@@ -0,0 +1,252 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::pattern::{self, Metadata};
+use crate::Cell;
+
+const EXTENSIONS: &[&str] = &["rle", "cells", "mc"];
+
+// The directory comes from `LIFE_PATTERNS`, falling back to `patterns` in the
+// working directory.
+pub fn directory() -> PathBuf {
+    std::env::var_os("LIFE_PATTERNS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("patterns"))
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub name: String,
+    pub metadata: Metadata,
+    pub cells: Vec<Cell>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+// Pattern files found under a directory, sorted by name. Files that cannot be
+// read or parsed are kept as failures so they can be listed.
+#[derive(Debug)]
+pub struct Library {
+    directory: PathBuf,
+    entries: Vec<Entry>,
+    failures: Vec<Failure>,
+    fingerprint: Vec<(PathBuf, Option<SystemTime>, u64)>,
+    // Why the directory could not be listed in the last scan.
+    error: Option<String>,
+}
+
+impl Library {
+    pub fn open(directory: PathBuf) -> Self {
+        let mut library = Library {
+            directory,
+            entries: Vec::new(),
+            failures: Vec::new(),
+            fingerprint: Vec::new(),
+            error: None,
+        };
+
+        library.scan();
+        library
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn failures(&self) -> &[Failure] {
+        &self.failures
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Entry> {
+        self.entries
+            .binary_search_by_key(&name.to_lowercase(), |entry| entry.name.to_lowercase())
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+
+    // Rescans the directory if any pattern file was added, removed or
+    // modified, or the directory became readable or unreadable, since the
+    // last scan. Returns whether it did.
+    pub fn refresh(&mut self) -> bool {
+        let mut files = Vec::new();
+        let error = self.list(&mut files).err().map(|error| error.to_string());
+
+        if error == self.error && Self::fingerprint(&files) == self.fingerprint {
+            return false;
+        }
+
+        self.scan();
+        true
+    }
+
+    fn scan(&mut self) {
+        let mut files = Vec::new();
+
+        self.entries.clear();
+        self.failures.clear();
+
+        self.error = self.list(&mut files).err().map(|error| error.to_string());
+
+        if let Some(error) = &self.error {
+            self.failures.push(Failure {
+                path: self.directory.clone(),
+                message: error.clone(),
+            });
+        }
+
+        self.fingerprint = Self::fingerprint(&files);
+
+        for path in files {
+            match load(&path) {
+                Ok(entry) => self.entries.push(entry),
+                Err(message) => self.failures.push(Failure { path, message }),
+            }
+        }
+
+        self.entries.sort_by_key(|entry| entry.name.to_lowercase());
+    }
+
+    // Collects the pattern files under the directory, descending into
+    // subdirectories.
+    fn list(&self, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        let mut directories = vec![self.directory.clone()];
+
+        while let Some(directory) = directories.pop() {
+            for entry in fs::read_dir(&directory)? {
+                let path = entry?.path();
+
+                if path.is_dir() {
+                    directories.push(path);
+                } else if has_pattern_extension(&path) {
+                    files.push(path);
+                }
+            }
+        }
+
+        files.sort();
+        Ok(())
+    }
+
+    fn fingerprint(files: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>, u64)> {
+        files
+            .iter()
+            .map(|path| {
+                let metadata = fs::metadata(path).ok();
+
+                (
+                    path.clone(),
+                    metadata.as_ref().and_then(|metadata| metadata.modified().ok()),
+                    metadata.map_or(0, |metadata| metadata.len()),
+                )
+            })
+            .collect()
+    }
+}
+
+fn has_pattern_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+fn load(path: &Path) -> Result<Entry, String> {
+    let text = fs::read_to_string(path).map_err(|error| error.to_string())?;
+
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let cells = match extension.as_str() {
+        "rle" => pattern::parse_rle(&text),
+        "mc" => pattern::parse_macrocell(&text),
+        _ => pattern::parse_cells(&text),
+    }
+    .map_err(|error| error.to_string())?;
+
+    if cells.is_empty() {
+        return Err(pattern::Error::Empty.to_string());
+    }
+
+    let metadata = pattern::metadata(&text);
+
+    let name = metadata.name.clone().unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+
+    Ok(Entry {
+        name,
+        metadata,
+        cells: pattern::normalize(cells),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refreshes_only_on_changes() {
+        let directory = std::env::temp_dir().join(format!("life-library-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+
+        let mut library = Library::open(directory.clone());
+
+        assert_eq!(library.failures().len(), 1);
+        assert!(!library.refresh());
+
+        fs::create_dir(&directory).unwrap();
+
+        assert!(library.refresh());
+        assert!(library.failures().is_empty());
+        assert!(!library.refresh());
+
+        fs::write(directory.join("glider.rle"), "bo$2bo$3o!").unwrap();
+
+        assert!(library.refresh());
+        assert_eq!(library.entries().len(), 1);
+        assert!(!library.refresh());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn lists_oversized_files_as_failures() {
+        let directory = std::env::temp_dir().join(format!("life-library-oversized-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+
+        fs::create_dir(&directory).unwrap();
+
+        // Every level repeats the one below four times, 64 * 4^57 cells.
+        let mut macrocell = format!("[M2]\n{}\n", "********$".repeat(8));
+
+        for level in 4..=60 {
+            macrocell.push_str(&format!("{0} {1} {1} {1} {1}\n", level, level - 3));
+        }
+
+        fs::write(directory.join("huge.mc"), macrocell).unwrap();
+        fs::write(directory.join("huge.rle"), "99999999999999999999999o!").unwrap();
+        fs::write(directory.join("glider.rle"), "bo$2bo$3o!").unwrap();
+
+        let library = Library::open(directory.clone());
+        let message = pattern::Error::TooLarge.to_string();
+
+        assert_eq!(library.entries().len(), 1);
+        assert_eq!(library.failures().len(), 2);
+        assert!(library.failures().iter().all(|failure| failure.message == message));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::collections::{HashSet,HashMap};
 use iced::{
     Application, 
@@ -14,8 +15,16 @@ use iced::{
     Size,
     Vector,
     Subscription,
+    Row,
+    Button,
+    Text,
+    button,
     time,
+    keyboard,
     mouse,
+    window,
+    HorizontalAlignment,
+    VerticalAlignment,
     };
 use iced::canvas::{
     self,
@@ -23,42 +32,402 @@ use iced::canvas::{
     Canvas,
     Cursor,
     Geometry,
-    Path,
     Frame,
+    Path,
+    Stroke,
 };
 use iced::canvas::event::{self, Event};
-
+use iced_test::raster::{Pixmap, Shape, Surface};
+use bindings::{Action, Bindings};
+use browser::{Browser, Source};
+use controls::{Controls, Playback};
+use density::Summary;
+use follow::Tracker;
+use heatmap::Heatmap;
+use inspector::{Ages, Inspection};
+use history::History;
+use library::Library;
+use minimap::Minimap;
+use preset::Preset;
+use recorder::Recorder;
+use selection::{Edit, Selection};
+use session::{Bookmark, Session};
+use stats::{Sample, Stats};
+use theme::Theme;
+use tiles::Tiles;
+use tools::Tool;
+use trails::Trails;
+use zoom::Limits;
+
+mod apgcode;
+mod bindings;
+mod browser;
+mod camera;
+mod clipboard;
+mod controls;
+mod density;
+mod follow;
+mod heatmap;
+mod history;
+mod inspector;
+mod library;
+mod minimap;
+mod pattern;
+mod preset;
+mod preview;
+mod recorder;
+mod selection;
+mod session;
+mod stats;
+mod svg;
+mod theme;
+mod tiles;
+mod tools;
+mod trails;
+mod zoom;
 
 pub fn main() -> iced::Result {
 
-    GameOfLife::run(Settings {
-        antialiasing: true,
-        ..Settings::default()
-    })
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.as_slice() {
+        [command, code] if command == "canonical" => {
+            let life: Life = decode_or_exit(code).into_iter().collect();
+
+            match apgcode::encode(&life) {
+                Some(canonical) => println!("{}", canonical),
+                None => {
+                    eprintln!("`{}` is not a still life, oscillator or spaceship", code);
+                    std::process::exit(1);
+                }
+            }
+
+            Ok(())
+        },
+        [command, path, from, to, options @ ..] if command == "record" => {
+            let (recorder, code) = parse_recorder(from, to, options).unwrap_or_else(|error| {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            });
+
+            let life = match code {
+                Some(code) => decode_or_exit(code).into_iter().collect(),
+                None => Grid::default().life,
+            };
+
+            if let Err(error) = recorder.record(&life, path) {
+                eprintln!("Could not record {}: {}", path, error);
+                std::process::exit(1);
+            }
+
+            Ok(())
+        },
+        [command, path, options @ ..] if command == "snapshot" => {
+            let (width, height) = window::Settings::default().size;
+
+            let grid = match options.first() {
+                Some(code) => Grid::from_cells(decode_or_exit(code)),
+                None => Grid::default(),
+            };
+
+            if let Err(error) = grid.snapshot(width, height).save_png(path) {
+                eprintln!("Could not write {}: {}", path, error);
+                std::process::exit(1);
+            }
+
+            Ok(())
+        },
+        [name] => {
+            let cells = match Library::open(library::directory()).find(name) {
+                Some(entry) => entry.cells.clone(),
+                None => decode_or_exit(name),
+            };
+
+            GameOfLife::run(Settings {
+                antialiasing: true,
+                flags: Some(cells),
+                ..Settings::default()
+            })
+        },
+        _ => GameOfLife::run(Settings {
+            antialiasing: true,
+            ..Settings::default()
+        }),
+    }
+
+}
+
+fn parse_recorder<'a>(from: &str, to: &str, options: &'a [String]) -> Result<(Recorder, Option<&'a str>), String> {
+    let generation = |value: &str| value.parse::<usize>()
+        .map_err(|_| format!("invalid generation `{}`", value));
+
+    let generations = generation(from)?..generation(to)?;
+
+    if generations.is_empty() {
+        return Err(format!("no generations from {} to {}", from, to));
+    }
+
+    let mut recorder = Recorder {
+        generations,
+        ..Recorder::default()
+    };
+    let mut code = None;
+
+    for option in options {
+        let (key, value) = match option.find('=') {
+            Some(index) => (&option[..index], &option[index + 1..]),
+            None => {
+                code = Some(option.as_str());
+                continue;
+            }
+        };
+
+        let invalid = || format!("invalid value for `{}`: `{}`", key, value);
+
+        match key {
+            "delay" => recorder.delay = value.parse().map_err(|_| invalid())?,
+            "scale" => recorder.scale = match value.parse() {
+                Ok(scale) if scale > 0 => scale,
+                _ => return Err(invalid()),
+            },
+            "margin" => recorder.crop = recorder::Crop::Fit {
+                margin: value.parse().map_err(|_| invalid())?,
+            },
+            "crop" => {
+                let values = value
+                    .split(',')
+                    .map(|value| value.parse::<isize>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| invalid())?;
+
+                recorder.crop = match values.as_slice() {
+                    [i, j, rows, columns] if *rows > 0 && *columns > 0 => recorder::Crop::Fixed {
+                        origin: Cell { i: *i, j: *j },
+                        rows: *rows as usize,
+                        columns: *columns as usize,
+                    },
+                    _ => return Err(invalid()),
+                };
+            },
+            "background" => recorder.palette.background = hex_color(value).ok_or_else(invalid)?,
+            "cell" => recorder.palette.cell = hex_color(value).ok_or_else(invalid)?,
+            _ => return Err(format!("unknown option `{}`", key)),
+        }
+    }
+
+    Ok((recorder, code))
+}
+
+// The 8-bit channels of a color, ignoring alpha.
+fn rgb8(color: Color) -> [u8; 3] {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    [channel(color.r), channel(color.g), channel(color.b)]
+}
+
+// Reads `#rrggbb`, or `#rrggbbaa` with an alpha channel.
+fn hex_color(hex: &str) -> Option<Color> {
+    let hex = hex.trim_start_matches('#');
+
+    if hex.len() != 6 && hex.len() != 8 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+
+    let alpha = match hex.len() {
+        8 => channel(6)? as f32 / 255.0,
+        _ => 1.0,
+    };
+
+    Some(Color::from_rgba8(channel(0)?, channel(2)?, channel(4)?, alpha))
+}
 
+fn decode_or_exit(code: &str) -> Vec<Cell> {
+    apgcode::decode(code).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    })
 }
 
-#[derive(Default)]
 struct GameOfLife {
     grid: Grid,
+    library: Library,
+    browser: Browser,
+    show_browser: bool,
+    clipboard: Box<dyn clipboard::Clipboard>,
+    playback: Playback,
+    controls: Controls,
+    selection_bar: selection::Bar,
+    trails_bar: trails::Bar,
+    heatmap_bar: heatmap::Bar,
+    preview_bar: preview::Bar,
+    inspector_bar: inspector::Bar,
+    palette: tools::Palette,
+    session: Session,
+    camera: camera::Panel,
+    minimap: Minimap,
+    stats: stats::Panel,
+    themes: Vec<Theme>,
+    theme: usize,
+    undo: button::State,
+    redo: button::State,
+    lines: button::State,
+    next_theme: button::State,
+    browse: button::State,
+    export_pattern: button::State,
+    export_view: button::State,
+    copy: button::State,
+    paste: button::State,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Message {
     Tick,
     Populate(Cell),
     Unpopulate(Cell),
+    Draw(Vec<Cell>),
+    Erase(Vec<Cell>),
+    ExportSvg(svg::Extent),
+    Copy,
+    Paste,
+    CancelPaste,
+    Place(Cell),
+    ToggleBrowser,
+    LoadPattern(Source),
+    PlacePattern(Source),
+    RefreshLibrary,
+    TogglePlayback,
+    Step,
+    Reset,
+    Clear,
+    SpeedChanged(u16),
+    GenerationsPerFrameChanged(u16),
+    SelectTool(Tool),
+    Select(Option<Selection>),
+    Edit(Edit),
+    Undo,
+    Redo,
+    Perform(Action),
+    Fit,
+    CoordinateChanged(String),
+    GoTo,
+    BookmarkNameChanged(String),
+    AddBookmark,
+    ShowBookmark(usize),
+    RemoveBookmark(usize),
+    Follow(Cell),
+    Unfollow,
+    ToggleLines,
+    NextTheme,
+    ToggleTrails,
+    TrailLengthChanged(u16),
+    NextFade,
+    ToggleHeatmap,
+    HeatmapLengthChanged(u16),
+    ResetHeatmap,
+    ExportHeatmap,
+    ViewChanged,
+    MoveView(Point),
+    TogglePreview,
+    PreviewGenerationsChanged(u16),
+    Inspect(Cell),
+    ToggleFates,
+    StatsWindowChanged(u16),
+    ExportStats,
 }
 
 impl Application for GameOfLife {
     type Message = Message;
     type Executor = executor::Default;
-    type Flags = ();
+    type Flags = Option<Vec<Cell>>;
+
+    fn new(flags: Option<Vec<Cell>>) -> (Self, Command<Message>) {
+        let mut grid = match flags {
+            Some(cells) => Grid::from_cells(cells),
+            None => Grid::default(),
+        };
+
+        let library = Library::open(library::directory());
+
+        let (bindings, errors) = Bindings::load(bindings::path());
+
+        for error in errors {
+            eprintln!("{}", error);
+        }
+
+        grid.bindings = bindings;
+
+        let (limits, errors) = Limits::load();
+
+        for error in errors {
+            eprintln!("{}", error);
+        }
+
+        grid.set_limits(limits);
+
+        let mut themes = Theme::presets();
+        let (theme, errors) = Theme::load(theme::path());
+
+        for error in errors {
+            eprintln!("{}", error);
+        }
+
+        // A theme file is selected over the presets.
+        let theme = match theme {
+            Some(theme) => {
+                themes.push(theme);
+                themes.len() - 1
+            },
+            None => 0,
+        };
+
+        grid.set_theme(themes[theme].clone());
+
+        let mut minimap = Minimap::new(&themes[theme]);
+        minimap.set_life(&grid.life, grid.revision);
+
+        grid.stats.observe(&grid.life, grid.generation, grid.revision);
+
+        let mut stats = stats::Panel::new(&themes[theme]);
+        stats.set_stats(&grid.stats);
+
+        let (session, errors) = Session::load(session::path());
+
+        for error in errors {
+            eprintln!("{}", error);
+        }
 
-    fn new(_flags: ()) -> (Self, Command<Message>) {
         (
             Self {
-                ..Self::default()
+                grid,
+                browser: Browser::new(&library, &themes[theme]),
+                library,
+                show_browser: false,
+                clipboard: clipboard::system(),
+                playback: Playback::default(),
+                controls: Controls::default(),
+                selection_bar: selection::Bar::default(),
+                trails_bar: trails::Bar::default(),
+                heatmap_bar: heatmap::Bar::default(),
+                preview_bar: preview::Bar::default(),
+                inspector_bar: inspector::Bar::default(),
+                palette: tools::Palette::default(),
+                session,
+                camera: camera::Panel::default(),
+                minimap,
+                stats,
+                themes,
+                theme,
+                undo: button::State::new(),
+                redo: button::State::new(),
+                lines: button::State::new(),
+                next_theme: button::State::new(),
+                browse: button::State::new(),
+                export_pattern: button::State::new(),
+                export_view: button::State::new(),
+                copy: button::State::new(),
+                paste: button::State::new(),
             },
             Command::none()
         )
@@ -70,36 +439,367 @@ impl Application for GameOfLife {
 
     fn update(&mut self, message: Message) -> Command<Message> {
 
+        // Only the camera moves with these, so the overview keeps its counts.
+        let is_camera_only = matches!(message, Message::ViewChanged | Message::MoveView(_));
+
         match message {
             Message::Tick => {
-                self.grid.update();
+                self.grid.update(self.playback.generations_per_frame as usize);
             },
             Message::Populate(cell) => {
                 self.grid.populate(cell);
             },
             Message::Unpopulate(cell) => {
                 self.grid.unpopulate(&cell);
-            }
+            },
+            Message::Draw(cells) => {
+                self.grid.draw(cells);
+            },
+            Message::Erase(cells) => {
+                self.grid.erase(cells);
+            },
+            Message::ExportSvg(extent) => {
+                let path = match extent {
+                    svg::Extent::Pattern => "pattern.svg",
+                    svg::Extent::View => "view.svg",
+                };
+
+                if let Err(error) = std::fs::write(path, self.grid.to_svg(extent, &self.grid.svg_options())) {
+                    eprintln!("Could not write {}: {}", path, error);
+                }
+            },
+            Message::Copy => {
+                let rle = match self.grid.selection {
+                    Some(selection) => pattern::to_rle(&selection.cells(&self.grid.life)),
+                    None => pattern::to_rle(&self.grid.life.cells),
+                };
+
+                self.clipboard.write(rle);
+            },
+            Message::Paste => {
+                match self.clipboard.read().as_deref().map(pattern::parse) {
+                    Some(Ok(cells)) => self.grid.start_paste(cells),
+                    Some(Err(error)) => eprintln!("Could not paste: {}", error),
+                    None => {},
+                }
+            },
+            Message::CancelPaste => {
+                self.grid.cancel_paste();
+            },
+            Message::Place(origin) => {
+                self.grid.place(origin);
+            },
+            Message::ToggleBrowser => {
+                self.show_browser = !self.show_browser;
+
+                if self.show_browser && self.library.refresh() {
+                    self.browser.set_library(&self.library);
+                }
+            },
+            Message::LoadPattern(source) => {
+                if let Some(cells) = self.pattern_cells(source) {
+                    self.grid.load(cells);
+                }
+            },
+            Message::PlacePattern(source) => {
+                if let Some(cells) = self.pattern_cells(source) {
+                    self.grid.start_paste(cells);
+                }
+            },
+            Message::RefreshLibrary => {
+                if self.library.refresh() {
+                    self.browser.set_library(&self.library);
+                }
+            },
+            Message::TogglePlayback => {
+                self.playback.is_playing = !self.playback.is_playing;
+            },
+            Message::Step => {
+                self.grid.update(self.playback.generations_per_frame as usize);
+            },
+            Message::Reset => {
+                self.grid.reset();
+            },
+            Message::Clear => {
+                self.grid.clear();
+            },
+            Message::SpeedChanged(speed) => {
+                self.playback.speed = speed;
+            },
+            Message::GenerationsPerFrameChanged(generations) => {
+                self.playback.generations_per_frame = generations;
+            },
+            Message::SelectTool(tool) => {
+                self.grid.tool = tool;
+            },
+            Message::Select(selection) => {
+                self.grid.selection = selection;
+            },
+            Message::Edit(Edit::Cut) => {
+                if let Some(selection) = self.grid.selection {
+                    self.clipboard.write(pattern::to_rle(&selection.cells(&self.grid.life)));
+                    self.grid.edit(Edit::Delete);
+                }
+            },
+            Message::Edit(edit) => {
+                self.grid.edit(edit);
+            },
+            Message::Undo => {
+                self.grid.undo();
+            },
+            Message::Redo => {
+                self.grid.redo();
+            },
+            Message::Perform(action) => {
+                if !self.camera.is_editing() {
+                    if let Some(message) = self.grid.perform(action) {
+                        return self.update(message);
+                    }
+                }
+            },
+            Message::Fit => {
+                self.grid.fit();
+            },
+            Message::ToggleTrails => {
+                self.grid.trail.is_enabled = !self.grid.trail.is_enabled;
+                self.grid.trails.clear();
+                self.grid.redraw();
+            },
+            Message::TrailLengthChanged(length) => {
+                self.grid.trail.length = length;
+                self.grid.redraw();
+            },
+            Message::NextFade => {
+                self.grid.trail.fade = self.grid.trail.fade.next();
+                self.grid.redraw();
+            },
+            Message::ToggleHeatmap => {
+                self.grid.show_heatmap = !self.grid.show_heatmap;
+
+                // Hiding keeps the counts, so they can still be exported.
+                if self.grid.show_heatmap {
+                    self.grid.heatmap.reset(self.grid.generation);
+                }
+
+                self.grid.redraw();
+            },
+            Message::HeatmapLengthChanged(length) => {
+                self.grid.heatmap.set_length(length as usize);
+                self.grid.redraw();
+            },
+            Message::ResetHeatmap => {
+                self.grid.heatmap.reset(self.grid.generation);
+                self.grid.redraw();
+            },
+            Message::ExportHeatmap => {
+                let path = "heatmap.csv";
+
+                if let Err(error) = std::fs::write(path, self.grid.heatmap.to_csv()) {
+                    eprintln!("Could not write {}: {}", path, error);
+                }
+            },
+            Message::NextTheme => {
+                self.theme = (self.theme + 1) % self.themes.len();
+                self.grid.set_theme(self.themes[self.theme].clone());
+                self.browser.set_theme(&self.themes[self.theme]);
+                self.minimap.set_theme(&self.themes[self.theme]);
+                self.stats.set_theme(&self.themes[self.theme]);
+            },
+            Message::ToggleLines => {
+                self.grid.show_lines = !self.grid.show_lines;
+                self.grid.redraw();
+            },
+            Message::CoordinateChanged(value) => {
+                self.camera.set_coordinate(value);
+            },
+            Message::GoTo => {
+                if let Some(cell) = self.camera.coordinate() {
+                    self.grid.center_on(cell);
+                }
+            },
+            Message::BookmarkNameChanged(value) => {
+                self.camera.set_name(value);
+            },
+            Message::AddBookmark => {
+                let (translation, scaling) = self.grid.camera();
+
+                self.session.bookmarks.push(Bookmark {
+                    name: self.camera.take_name(self.session.bookmarks.len()),
+                    translation,
+                    scaling,
+                });
+                self.save_session();
+            },
+            Message::ShowBookmark(index) => {
+                if let Some(bookmark) = self.session.bookmarks.get(index) {
+                    self.grid.set_camera(bookmark.translation, bookmark.scaling);
+                }
+            },
+            Message::RemoveBookmark(index) => {
+                if index < self.session.bookmarks.len() {
+                    self.session.bookmarks.remove(index);
+                    self.save_session();
+                }
+            },
+            Message::Follow(cell) => {
+                self.grid.follow(cell);
+            },
+            Message::Unfollow => {
+                self.grid.following = None;
+            },
+            Message::TogglePreview => {
+                self.grid.preview.is_enabled = !self.grid.preview.is_enabled;
+            },
+            Message::PreviewGenerationsChanged(generations) => {
+                self.grid.preview.generations = generations;
+            },
+            Message::Inspect(cell) => {
+                self.grid.inspected = Some(cell);
+            },
+            Message::ToggleFates => {
+                self.grid.show_fates = !self.grid.show_fates;
+                self.grid.redraw();
+            },
+            Message::StatsWindowChanged(window) => {
+                self.stats.set_window(window);
+                self.stats.set_stats(&self.grid.stats);
+            },
+            Message::ExportStats => {
+                let path = "stats.csv";
+
+                if let Err(error) = std::fs::write(path, self.grid.stats.to_csv()) {
+                    eprintln!("Could not write {}: {}", path, error);
+                }
+            },
+            Message::ViewChanged => {},
+            Message::MoveView(center) => {
+                self.grid.center_at(center);
+            },
+        }
+
+        if !is_camera_only {
+            self.minimap.set_life(&self.grid.life, self.grid.revision);
+            self.grid.update_future(!self.playback.is_playing);
+            self.grid.stats.observe(&self.grid.life, self.grid.generation, self.grid.revision);
+            self.stats.set_stats(&self.grid.stats);
         }
 
+        self.minimap.set_view(self.grid.visible_area());
+
         Command::none()
     }
 
+    // Nothing runs while paused; the library is then refreshed when the
+    // browser opens.
     fn subscription(&self) -> Subscription<Message>{
-        time::every(std::time::Duration::from_millis(50))
-            .map(|_instant| {
-                Message::Tick
-             } )
+        if self.playback.is_playing {
+            Subscription::batch(vec![
+                time::every(self.playback.interval())
+                    .map(|_instant| {
+                        Message::Tick
+                     } ),
+                time::every(std::time::Duration::from_secs(2))
+                    .map(|_instant| Message::RefreshLibrary),
+            ])
+        } else {
+            Subscription::none()
+        }
     }
 
     fn view(&mut self) -> Element<Message> {
 
+        let is_pasting = self.grid.is_pasting();
+        let generation = self.grid.generation;
+        let tool = self.grid.tool;
+        let selection = self.grid.selection;
+        let can_undo = self.grid.history.can_undo();
+        let can_redo = self.grid.history.can_redo();
+        let is_following = self.grid.following.is_some();
+        let trail = self.grid.trail;
+        let show_heatmap = self.grid.show_heatmap;
+        let preview = self.grid.preview;
+        let show_fates = self.grid.show_fates;
+        let inspection = self.grid.inspection();
+        let heatmap_length = self.grid.heatmap.length();
+        let heatmap_start = self.grid.heatmap.start();
+        let heatmap_generations = self.grid.heatmap.generations();
+
         let canvas: Element<Message> = Canvas::new(&mut self.grid)
                     .width(Length::Fill)
                     .height(Length::Fill)
                     .into();
 
-        let content = Column::new().push(canvas);
+        let controls = Row::new()
+            .padding(10)
+            .spacing(10)
+            .push(self.palette.view(tool))
+            .push({
+                let undo = Button::new(&mut self.undo, Text::new("Deshacer"));
+
+                if can_undo { undo.on_press(Message::Undo) } else { undo }
+            })
+            .push({
+                let redo = Button::new(&mut self.redo, Text::new("Rehacer"));
+
+                if can_redo { redo.on_press(Message::Redo) } else { redo }
+            })
+            .push(
+                Button::new(&mut self.lines, Text::new("Cuadrícula"))
+                    .on_press(Message::ToggleLines)
+            )
+            .push(
+                Button::new(&mut self.next_theme, Text::new(format!("Tema: {}", self.themes[self.theme].name)))
+                    .on_press(Message::NextTheme)
+            )
+            .push(
+                Button::new(&mut self.browse, Text::new("Patrones"))
+                    .on_press(Message::ToggleBrowser)
+            )
+            .push(
+                Button::new(&mut self.export_pattern, Text::new("SVG (patrón)"))
+                    .on_press(Message::ExportSvg(svg::Extent::Pattern))
+            )
+            .push(
+                Button::new(&mut self.export_view, Text::new("SVG (vista)"))
+                    .on_press(Message::ExportSvg(svg::Extent::View))
+            )
+            .push(
+                Button::new(&mut self.copy, Text::new("Copiar RLE"))
+                    .on_press(Message::Copy)
+            )
+            .push(if is_pasting {
+                Button::new(&mut self.paste, Text::new("Cancelar pegado"))
+                    .on_press(Message::CancelPaste)
+            } else {
+                Button::new(&mut self.paste, Text::new("Pegar"))
+                    .on_press(Message::Paste)
+            });
+
+        let mut body = Row::new()
+            .height(Length::Fill)
+            .push(canvas)
+            .push(Container::new(self.minimap.view()).padding(10));
+
+        if self.show_browser {
+            body = body.push(self.browser.view());
+        }
+
+        let mut content = Column::new()
+            .push(body)
+            .push(self.stats.view())
+            .push(self.controls.view(self.playback, generation))
+            .push(self.trails_bar.view(trail))
+            .push(self.heatmap_bar.view(show_heatmap, heatmap_length, heatmap_start, heatmap_generations))
+            .push(self.preview_bar.view(preview))
+            .push(self.inspector_bar.view(show_fates, inspection));
+
+        if let Some(selection) = selection {
+            content = content.push(self.selection_bar.view(selection));
+        }
+
+        let content = content.push(self.camera.view(&self.session.bookmarks, is_following));
+
+        let content = content.push(controls);
 
         Container::new(content)
             .width(Length::Fill)
@@ -108,11 +808,28 @@ impl Application for GameOfLife {
     }
 }
 
+impl GameOfLife {
+    fn save_session(&self) {
+        if let Err(error) = self.session.save(session::path()) {
+            eprintln!("Could not save the session: {}", error);
+        }
+    }
+
+    fn pattern_cells(&self, source: Source) -> Option<Vec<Cell>> {
+        match source {
+            Source::Preset(index) => preset::CATALOG.get(index).map(Preset::cells),
+            Source::Library(index) => self.library.entries().get(index).map(|entry| entry.cells.clone()),
+        }
+    }
+}
+
 enum Interaction {
     None,
     Panning {translation: Vector, start: Point},
-    Drawing,
-    Erasing,
+    Drawing {last: Cell},
+    Erasing {last: Cell},
+    Shaping {start: Cell, end: Cell},
+    Selecting {start: Cell},
 }
 
 pub struct Region {
@@ -122,20 +839,65 @@ pub struct Region {
     height: f32,
 }
 
+type FillPreview = ((Cell, Selection, usize), Vec<Cell>);
+
 struct Grid {
     life_cache: Cache,
+    tiles: Tiles,
+    marks_cache: Cache,
     life: Life,
+    // Bumped on every change to `life`, so what is derived from it can tell
+    // when it is stale.
+    revision: usize,
+    fill_preview: RefCell<Option<FillPreview>>,
     interaction: Interaction,
     translation: Vector,
     scaling: f32,
+    limits: Limits,
+    size: Size,
+    paste: Option<Vec<Cell>>,
+    initial: Life,
+    generation: usize,
+    tool: Tool,
+    selection: Option<Selection>,
+    history: History,
+    bindings: Bindings,
+    following: Option<Tracker>,
+    show_lines: bool,
+    theme: Theme,
+    trails: Trails,
+    trail: trails::Settings,
+    heatmap: Heatmap,
+    show_heatmap: bool,
+    future: RefCell<preview::Future>,
+    preview: preview::Settings,
+    ages: Ages,
+    inspected: Option<Cell>,
+    show_fates: bool,
+    stats: Stats,
 }
 
 impl canvas::Program<Message> for Grid {
 
     fn update(&mut self, event: Event, bounds: Rectangle, cursor: Cursor) -> (event::Status, Option<Message>) {
 
+        self.size = bounds.size();
+
+        if let Event::Keyboard(keyboard::Event::KeyPressed { key_code, modifiers }) = event {
+            return match self.bindings.action(key_code, modifiers) {
+                Some(action) => (event::Status::Captured, Some(Message::Perform(action))),
+                None => (event::Status::Ignored, None),
+            };
+        }
+
         if let Event::Mouse(mouse::Event::ButtonReleased(_)) = event {
-            self.interaction = Interaction::None;
+            let interaction = std::mem::replace(&mut self.interaction, Interaction::None);
+
+            self.history.end_stroke();
+
+            if let Interaction::Shaping {start, end} = interaction {
+                return (event::Status::Captured, Some(Message::Draw(self.tool.shape(start, end))));
+            }
         }
 
         let cursor_position = if let Some(position) = cursor.position_in(&bounds) {
@@ -157,16 +919,45 @@ impl canvas::Program<Message> for Grid {
             Event::Mouse(mouse_event) => match mouse_event {
                 mouse::Event::ButtonPressed(button) => {
                     let message = match button {
+                        mouse::Button::Left if self.paste.is_some() => {
+                            Some(Message::Place(self.paste_origin(cell)))
+                        },
+                        mouse::Button::Left if self.tool == Tool::Select => {
+                            self.interaction = Interaction::Selecting { start: cell };
+
+                            Some(Message::Select(Some(Selection::new(cell, cell))))
+                        },
+                        mouse::Button::Left if self.tool.is_shape() => {
+                            self.interaction = Interaction::Shaping { start: cell, end: cell };
+
+                            None
+                        },
+                        mouse::Button::Left if self.tool == Tool::Follow => {
+                            Some(Message::Follow(cell))
+                        },
+                        mouse::Button::Left if self.tool == Tool::Inspect => {
+                            Some(Message::Inspect(cell))
+                        },
+                        mouse::Button::Left if self.tool == Tool::Fill => {
+                            self.fill_region(cell).map(|cells| if is_populated {
+                                Message::Erase(cells)
+                            } else {
+                                Message::Draw(cells)
+                            })
+                        },
                         mouse::Button::Left => {
+                            self.history.begin_stroke();
+
                             self.interaction = if is_populated {
-                                Interaction::Erasing
+                                Interaction::Erasing { last: cell }
                             } else {
-                                Interaction::Drawing
+                                Interaction::Drawing { last: cell }
                             };
 
                             populate.or(unpopulate)
                         },
                         mouse::Button::Right => {
+                            self.following = None;
                             self.interaction = Interaction::Panning {
                                 translation: self.translation,
                                 start: cursor_position,
@@ -181,16 +972,32 @@ impl canvas::Program<Message> for Grid {
                 },
                 mouse::Event::CursorMoved {..} => {
                     let message = match self.interaction {
-                        Interaction::Drawing => populate,
-                        Interaction::Erasing => unpopulate,
+                        Interaction::Drawing {last} if last != cell => {
+                            self.interaction = Interaction::Drawing { last: cell };
+
+                            Some(Message::Draw(tools::line(last, cell)))
+                        },
+                        Interaction::Erasing {last} if last != cell => {
+                            self.interaction = Interaction::Erasing { last: cell };
+
+                            Some(Message::Erase(tools::line(last, cell)))
+                        },
+                        Interaction::Shaping {start, ..} => {
+                            self.interaction = Interaction::Shaping { start, end: cell };
+
+                            None
+                        },
+                        Interaction::Selecting {start} => {
+                            Some(Message::Select(Some(Selection::new(start, cell))))
+                        },
                         Interaction::Panning {translation, start} => {
                             self.translation = translation
                                 + (cursor_position - start)
                                 * (1.0 / self.scaling);
     
-                            self.life_cache.clear();
+                            self.redraw();
     
-                            None
+                            Some(Message::ViewChanged)
                         },
                         _ => None,
                     };
@@ -205,15 +1012,15 @@ impl canvas::Program<Message> for Grid {
                 mouse::Event::WheelScrolled { delta } => match delta {
                     mouse::ScrollDelta::Lines { y, .. } |
                     mouse::ScrollDelta::Pixels { y, .. } => {
-                        if y < 0.0 && self.scaling > Self::MIN_SCALING
-                            || y > 0.0 && self.scaling < Self::MAX_SCALING
+                        if y < 0.0 && self.scaling > self.limits.min
+                            || y > 0.0 && self.scaling < self.limits.max
                         {
                             let old_scaling = self.scaling;
 
                             self.scaling = (self.scaling
                                 *(1.0 + y / 30.0))
-                                .max(Self::MIN_SCALING)
-                                .min(Self::MAX_SCALING);
+                                .max(self.limits.min)
+                                .min(self.limits.max);
 
                             if let Some(cursor_to_center) = 
                                 cursor.position_from(bounds.center())
@@ -227,10 +1034,10 @@ impl canvas::Program<Message> for Grid {
                                     );
                             }
 
-                            self.life_cache.clear();
+                            self.redraw();
                         }
 
-                        (event::Status::Captured, None)
+                        (event::Status::Captured, Some(Message::ViewChanged))
                     }
                 }
                 _ => (event::Status::Ignored, None)
@@ -243,31 +1050,18 @@ impl canvas::Program<Message> for Grid {
 
         let center = Vector::new(bounds.width / 2.0, bounds.height / 2.0);
 
-        let grid = self.life_cache.draw(bounds.size(), |frame| {
-            let background = Path::rectangle(Point::ORIGIN, frame.size());
-
-            frame.fill(&background, Color::BLACK);
-
-            frame.with_save(|frame| {
-
-                frame.translate(center);
-                frame.scale(self.scaling);
-                frame.translate(self.translation);
-                frame.scale(Cell::SIZE as f32);
-
-                for cell in &self.life.cells {
-
-                    frame.fill_rectangle(
-                        Point::new(cell.j as f32, cell.i as f32),
-                        Size::UNIT,
-                        Color::WHITE,
-                    )
-
-                }
+        let below = self.life_cache.draw(bounds.size(), |frame| self.draw_below(frame));
 
-            });
+        let cells = self.tiles.geometries(
+            &self.life,
+            &self.cells_in(bounds.size()),
+            self.level(),
+            bounds.size(),
+            (self.translation, self.scaling),
+            |frame| self.transform(frame),
+        );
 
-        });
+        let above = self.marks_cache.draw(bounds.size(), |frame| self.draw_above(frame));
 
         let overlay = {
             let mut frame = Frame::new(bounds.size());
@@ -286,42 +1080,468 @@ impl canvas::Program<Message> for Grid {
                     frame.fill_rectangle(
                         Point::new(cell.j as f32, cell.i as f32),
                         Size::UNIT,
-                        Color {
-                            a: 0.5,
-                            ..Color::WHITE
+                        self.theme.hover,
+                    );
+
+                    match self.interaction {
+                        Interaction::Shaping {start, end} => {
+                            for preview in self.tool.shape(start, end) {
+                                frame.fill_rectangle(
+                                    Point::new(preview.j as f32, preview.i as f32),
+                                    Size::UNIT,
+                                    self.theme.preview,
+                                );
+                            }
+                        },
+                        _ if self.tool == Tool::Fill && self.paste.is_none() => {
+                            self.with_fill_preview(cell, |preview| {
+                                let rectangles = preview
+                                    .iter()
+                                    .map(|cell| (Point::new(cell.j as f32, cell.i as f32), Size::UNIT))
+                                    .collect();
+
+                                frame.fill(
+                                    &Shape::Rectangles(rectangles).path(),
+                                    Color { a: self.theme.preview.a / 2.0, ..self.theme.preview },
+                                );
+                            });
+                        },
+                        _ => {},
+                    }
+
+                    if let Some(paste) = &self.paste {
+                        let origin = self.paste_origin(cell);
+
+                        for ghost in paste {
+                            frame.fill_rectangle(
+                                Point::new((origin.j + ghost.j) as f32, (origin.i + ghost.i) as f32),
+                                Size::UNIT,
+                                self.theme.preview,
+                            );
+                        }
+                    }
+                });
+            }
+
+            if let Some(tracker) = &self.following {
+                let bounds = tracker.bounds();
+
+                frame.with_save(|frame| {
+                    frame.translate(center);
+                    frame.scale(self.scaling);
+                    frame.translate(self.translation);
+                    frame.scale(Cell::SIZE as f32);
+
+                    frame.stroke(
+                        &Path::rectangle(
+                            Point::new(bounds.top_left.j as f32 - 1.0, bounds.top_left.i as f32 - 1.0),
+                            Size::new(bounds.columns() as f32 + 2.0, bounds.rows() as f32 + 2.0),
+                        ),
+                        Stroke {
+                            color: self.theme.follow,
+                            width: 1.0,
+                            ..Stroke::default()
+                        },
+                    );
+                });
+            }
+
+            if let Some(cell) = self.inspected {
+                frame.with_save(|frame| {
+                    frame.translate(center);
+                    frame.scale(self.scaling);
+                    frame.translate(self.translation);
+                    frame.scale(Cell::SIZE as f32);
+
+                    frame.stroke(
+                        &Path::rectangle(Point::new(cell.j as f32, cell.i as f32), Size::UNIT),
+                        Stroke {
+                            color: self.theme.selection,
+                            width: 2.0,
+                            ..Stroke::default()
                         },
                     );
                 });
             }
 
+            if let Some(selection) = self.selection {
+                frame.with_save(|frame| {
+                    frame.translate(center);
+                    frame.scale(self.scaling);
+                    frame.translate(self.translation);
+                    frame.scale(Cell::SIZE as f32);
+
+                    let outline = Path::rectangle(
+                        Point::new(selection.top_left.j as f32, selection.top_left.i as f32),
+                        Size::new(selection.columns() as f32, selection.rows() as f32),
+                    );
+
+                    frame.fill(&outline, Color { a: 0.1, ..self.theme.selection });
+                    frame.stroke(&outline, Stroke {
+                        color: self.theme.selection,
+                        width: 2.0,
+                        ..Stroke::default()
+                    });
+                });
+            }
+
+            if self.show_lines {
+                self.draw_rulers(&mut frame);
+            }
+
+            self.draw_status(&mut frame, hovered_cell);
+
             frame.into_geometry()
         };
 
-        vec![grid, overlay]
+        let mut geometries = vec![below];
+
+        geometries.extend(cells);
+        geometries.push(above);
+        geometries.push(overlay);
+        geometries
 
     }
 }
 
 impl Grid {
-    const MIN_SCALING: f32 = 0.1;
-    const MAX_SCALING: f32 = 2.0;
-
-    pub fn from_preset(preset: Preset) -> Self {
-        Self {
-            life: preset.life()
-                    .into_iter()
-                    .map(|(i,j)| Cell { i, j })
-                    .collect(),
+    const FILL_LIMIT: usize = 100_000;
+    const PAN_STEP: f32 = 50.0;
+    const ZOOM_STEP: f32 = 1.25;
+    const FOLLOW_SMOOTHING: f32 = 0.25;
+    const MAJOR_LINE: isize = 10;
+    const RULER_HEIGHT: f32 = 18.0;
+    const RULER_WIDTH: f32 = 48.0;
+    const RULER_SPACING: f32 = 60.0;
+    const STATUS_HEIGHT: f32 = 22.0;
+
+    pub fn from_preset(preset: &Preset) -> Self {
+        Self::from_cells(preset.cells())
+    }
+
+    pub fn from_cells(cells: Vec<Cell>) -> Self {
+        let mut grid = Self {
+            life: Life::default(),
+            revision: 0,
+            fill_preview: RefCell::new(None),
             life_cache: Cache::default(),
+            tiles: Tiles::default(),
+            marks_cache: Cache::default(),
             interaction: Interaction::None,
             translation: Vector::default(),
             scaling: 1.0,
+            limits: Limits::default(),
+            size: Size::ZERO,
+            paste: None,
+            initial: Life::default(),
+            generation: 0,
+            tool: Tool::Freehand,
+            selection: None,
+            history: History::default(),
+            bindings: Bindings::default(),
+            following: None,
+            show_lines: false,
+            theme: Theme::default(),
+            trails: Trails::default(),
+            trail: trails::Settings::default(),
+            heatmap: Heatmap::default(),
+            show_heatmap: false,
+            future: RefCell::new(preview::Future::default()),
+            preview: preview::Settings::default(),
+            ages: Ages::default(),
+            inspected: None,
+            show_fates: false,
+            stats: Stats::default(),
+        };
+
+        grid.load(cells);
+        grid.history = History::default();
+        grid
+    }
+
+    // Replaces the universe with the given cells, centered on the view.
+    pub fn load(&mut self, cells: Vec<Cell>) {
+        let height = cells.iter().map(|cell| cell.i + 1).max().unwrap_or(0);
+        let width = cells.iter().map(|cell| cell.j + 1).max().unwrap_or(0);
+
+        self.record(|grid| {
+            grid.life = Life::default();
+            grid.stamp(cells, Cell { i: -height / 2, j: -width / 2 });
+            grid.generation = 0;
+        });
+
+        self.translation = Vector::default();
+        self.initial = self.life.clone();
+    }
+
+    // Goes back to the last loaded pattern, keeping the view.
+    pub fn reset(&mut self) {
+        self.record(|grid| {
+            grid.life = grid.initial.clone();
+            grid.generation = 0;
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.record(|grid| {
+            grid.life = Life::default();
+            grid.generation = 0;
+        });
+    }
+
+    // Runs an edit that may touch many cells and records it as a single
+    // undoable step.
+    fn record(&mut self, edit: impl FnOnce(&mut Self)) {
+        let life = self.life.clone();
+        let generation = self.generation;
+        let selection = self.selection;
+
+        edit(self);
+
+        self.revision += 1;
+
+        for cell in life.cells.symmetric_difference(&self.life.cells) {
+            self.tiles.invalidate(cell);
+        }
+
+        self.ages.sync(&self.life, self.generation);
+
+        self.history.begin();
+        self.history.diff(&life, &self.life);
+        self.history.generation(generation, self.generation);
+        self.history.selection(selection, self.selection);
+        self.history.end();
+
+        self.redraw();
+    }
+
+    // Keyboard actions that only move the camera are handled here; the rest
+    // become messages for the application.
+    fn perform(&mut self, action: Action) -> Option<Message> {
+        let pan = Self::PAN_STEP / self.scaling;
+
+        match action {
+            Action::TogglePlayback => return Some(Message::TogglePlayback),
+            Action::Step => return Some(Message::Step),
+            Action::Undo => return Some(Message::Undo),
+            Action::Redo => return Some(Message::Redo),
+            Action::Tool(tool) => return Some(Message::SelectTool(tool)),
+            Action::ToggleLines => return Some(Message::ToggleLines),
+            Action::PanUp | Action::PanDown | Action::PanLeft | Action::PanRight
+                if self.following.is_some() =>
+            {
+                self.following = None;
+
+                return self.perform(action);
+            },
+            Action::PanUp => self.translation.y += pan,
+            Action::PanDown => self.translation.y -= pan,
+            Action::PanLeft => self.translation.x += pan,
+            Action::PanRight => self.translation.x -= pan,
+            Action::ZoomIn => self.zoom(Self::ZOOM_STEP),
+            Action::ZoomOut => self.zoom(1.0 / Self::ZOOM_STEP),
+            Action::Fit => self.fit(),
+        }
+
+        self.redraw();
+
+        None
+    }
+
+    fn zoom(&mut self, factor: f32) {
+        self.scaling = self.limits.clamp(self.scaling * factor);
+    }
+
+    // Centers the live cells and zooms so their bounding box fills the view,
+    // leaving a small margin.
+    pub fn fit(&mut self) {
+        let (top_left, bottom_right) = match self.life.bounding_box() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        self.following = None;
+
+        let size = Cell::SIZE as f32;
+        let width = (bottom_right.j - top_left.j + 1) as f32 * size;
+        let height = (bottom_right.i - top_left.i + 1) as f32 * size;
+
+        self.translation = Vector::new(
+            -(top_left.j as f32 * size + width / 2.0),
+            -(top_left.i as f32 * size + height / 2.0),
+        );
+
+        self.scaling = self.limits.clamp(0.9 * (self.size.width / width).min(self.size.height / height));
+
+        self.redraw();
+    }
+
+    fn follow(&mut self, cell: Cell) {
+        self.following = Tracker::pick(&self.life, cell);
+    }
+
+    pub fn center_on(&mut self, cell: Cell) {
+        self.center_at(Point::new(cell.j as f32 + 0.5, cell.i as f32 + 0.5));
+    }
+
+    // Centers the view on a point in cell units, x being the column.
+    pub fn center_at(&mut self, center: Point) {
+        self.following = None;
+        self.translation = Vector::new(-center.x, -center.y) * Cell::SIZE as f32;
+        self.redraw();
+    }
+
+    // The future is only shown while paused, for editing. It is computed
+    // when drawn, for the cells in view.
+    pub fn update_future(&mut self, is_paused: bool) {
+        let count = if self.preview.is_enabled && is_paused {
+            Some(self.preview.generations as usize)
+        } else {
+            None
+        };
+
+        if self.future.get_mut().show(count) {
+            self.redraw();
         }
     }
 
-    pub fn update(&mut self) {
-        self.life.tick();
+    pub fn inspection(&self) -> Option<Inspection> {
+        self.inspected.map(|cell| Inspection::new(&self.life, &self.ages, cell, self.generation))
+    }
+
+    // The tiles of cells track their own changes; what is drawn under and
+    // over them is redrawn whole.
+    fn redraw(&mut self) {
         self.life_cache.clear();
+        self.marks_cache.clear();
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.tiles.set_theme(&self.theme);
+        self.redraw();
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+        self.scaling = limits.clamp(self.scaling);
+        self.redraw();
+    }
+
+    pub fn camera(&self) -> (Vector, f32) {
+        (self.translation, self.scaling)
+    }
+
+    pub fn set_camera(&mut self, translation: Vector, scaling: f32) {
+        self.following = None;
+        self.translation = translation;
+        self.scaling = self.limits.clamp(scaling);
+        self.redraw();
+    }
+
+    pub fn undo(&mut self) {
+        if self.history.undo(&mut self.life, &mut self.generation, &mut self.selection) {
+            self.revision += 1;
+            self.ages.sync(&self.life, self.generation);
+            self.tiles.invalidate_all();
+            self.redraw();
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if self.history.redo(&mut self.life, &mut self.generation, &mut self.selection) {
+            self.revision += 1;
+            self.ages.sync(&self.life, self.generation);
+            self.tiles.invalidate_all();
+            self.redraw();
+        }
+    }
+
+    fn edit(&mut self, edit: Edit) {
+        let selection = match self.selection {
+            Some(selection) => selection,
+            None => return,
+        };
+
+        self.record(|grid| match edit {
+            Edit::Cut | Edit::Delete => selection.clear(&mut grid.life),
+            Edit::ClearOutside => selection.clear_outside(&mut grid.life),
+            Edit::Rotate => grid.selection = Some(selection.rotate(&mut grid.life)),
+            Edit::FlipHorizontal => selection.flip_horizontal(&mut grid.life),
+            Edit::FlipVertical => selection.flip_vertical(&mut grid.life),
+            Edit::Randomize => selection.randomize(&mut grid.life),
+            Edit::Deselect => grid.selection = None,
+        });
+    }
+
+    pub fn stamp(&mut self, cells: impl IntoIterator<Item = Cell>, origin: Cell) {
+        for cell in cells {
+            self.life.populate(Cell {
+                i: origin.i + cell.i,
+                j: origin.j + cell.j,
+            });
+        }
+
+        self.revision += 1;
+
+        self.redraw();
+    }
+
+    pub fn update(&mut self, generations: usize) {
+        self.history.advance(&self.life, self.generation);
+
+        for step in 0..generations {
+            let (mut births, mut deaths) = (0, 0);
+
+            for cell in self.life.tick() {
+                let is_alive = self.life.contains(&cell);
+
+                if is_alive {
+                    births += 1;
+                } else {
+                    deaths += 1;
+                }
+
+                self.tiles.invalidate(&cell);
+                self.ages.changed(cell, is_alive, self.generation + step + 1);
+
+                if self.show_heatmap {
+                    self.heatmap.changed(cell);
+                }
+
+                if self.trail.is_enabled {
+                    if is_alive {
+                        self.trails.born(&cell);
+                    } else {
+                        self.trails.died(cell, self.generation + step + 1);
+                    }
+                }
+            }
+
+            self.stats.record(Sample::new(&self.life, self.generation + step + 1, births, deaths));
+
+            if self.show_heatmap {
+                self.heatmap.advance();
+            }
+
+            if let Some(tracker) = &mut self.following {
+                if !tracker.advance(&self.life) {
+                    self.following = None;
+                }
+            }
+        }
+
+        if let Some(tracker) = &self.following {
+            let center = tracker.center();
+            let target = Vector::new(-center.x, -center.y) * Cell::SIZE as f32;
+
+            self.translation = self.translation + (target - self.translation) * Self::FOLLOW_SMOOTHING;
+        }
+
+        self.generation += generations;
+        self.revision += 1;
+        self.trails.prune(self.generation, self.trail.length as usize);
+        self.redraw();
     }
 
     fn visible_region(&self, size: Size) -> Region {
@@ -336,6 +1556,54 @@ impl Grid {
         }
     }
 
+    // The visible region in cell units.
+    fn visible_area(&self) -> Rectangle {
+        let region = self.visible_region(self.size);
+        let size = Cell::SIZE as f32;
+
+        Rectangle {
+            x: region.x / size,
+            y: region.y / size,
+            width: region.width / size,
+            height: region.height / size,
+        }
+    }
+
+    // The cells at least partially on screen.
+    fn visible_cells(&self) -> Selection {
+        self.cells_in(self.size)
+    }
+
+    fn cells_in(&self, size: Size) -> Selection {
+        let region = self.visible_region(size);
+
+        Selection::new(
+            Cell::at(Point::new(region.x, region.y)),
+            Cell::at(Point::new(region.x + region.width, region.y + region.height)),
+        )
+    }
+
+    // Flood fill bounded by the view, so filling outside a pattern stops at
+    // the edges of the screen.
+    fn fill_region(&self, start: Cell) -> Option<Vec<Cell>> {
+        tools::flood_fill(&self.life, start, self.visible_cells(), Self::FILL_LIMIT)
+    }
+
+    // The fill preview is only recomputed when the hovered cell, the view or
+    // the universe change, not on every frame.
+    fn with_fill_preview(&self, start: Cell, draw: impl FnOnce(&[Cell])) {
+        let key = (start, self.visible_cells(), self.revision);
+        let mut preview = self.fill_preview.borrow_mut();
+
+        if preview.as_ref().map(|(cached, _)| *cached) != Some(key) {
+            *preview = Some((key, self.fill_region(start).unwrap_or_default()));
+        }
+
+        if let Some((_, cells)) = preview.as_ref() {
+            draw(cells);
+        }
+    }
+
     fn project(&self, position: Point, size: Size) -> Point {
         let region = self.visible_region(size);
 
@@ -345,30 +1613,459 @@ impl Grid {
         )
     }
 
+    fn start_paste(&mut self, cells: Vec<Cell>) {
+        self.paste = Some(cells);
+    }
+
+    fn cancel_paste(&mut self) {
+        self.paste = None;
+    }
+
+    fn is_pasting(&self) -> bool {
+        self.paste.is_some()
+    }
+
+    // The pasted pattern floats centered on the hovered cell.
+    fn paste_origin(&self, hovered: Cell) -> Cell {
+        let paste = self.paste.as_deref().unwrap_or_default();
+
+        let height = paste.iter().map(|cell| cell.i + 1).max().unwrap_or(0);
+        let width = paste.iter().map(|cell| cell.j + 1).max().unwrap_or(0);
+
+        Cell {
+            i: hovered.i - height / 2,
+            j: hovered.j - width / 2,
+        }
+    }
+
+    fn place(&mut self, origin: Cell) {
+        if let Some(cells) = self.paste.take() {
+            self.record(|grid| grid.stamp(cells, origin));
+        }
+    }
+
+    fn draw_life(&self, frame: &mut impl Surface) {
+        let visible = self.cells_in(frame.size());
+
+        self.draw_below(frame);
+
+        frame.with_save(|frame| {
+            self.transform(frame);
+            self.tiles.draw(&self.life, &visible, self.level(), frame);
+        });
+
+        self.draw_above(frame);
+    }
+
+    // Scales the surface so one unit is one cell.
+    fn transform(&self, frame: &mut impl Surface) {
+        let center = frame.center() - Point::ORIGIN;
+
+        frame.translate(center);
+        frame.scale(self.scaling);
+        frame.translate(self.translation);
+        frame.scale(Cell::SIZE as f32);
+    }
+
+    // Below a pixel per cell, single cells blur into noise, so blocks of
+    // cells are shaded by how many of them are alive.
+    fn level(&self) -> usize {
+        let pixels = Cell::SIZE as f32 * self.scaling;
+
+        if pixels < 1.0 {
+            ((1.0 / pixels).log2().ceil() as usize).clamp(1, Summary::MAX_LEVEL)
+        } else {
+            0
+        }
+    }
+
+    // The background and the trails, under the cells.
+    fn draw_below(&self, frame: &mut impl Surface) {
+        let background = Shape::Rectangle {
+            top_left: Point::ORIGIN,
+            size: frame.size(),
+        };
+
+        frame.fill(&background, self.theme.background);
+
+        if self.level() == 0 && self.trail.is_enabled {
+            let visible = self.cells_in(frame.size());
+
+            frame.with_save(|frame| {
+                self.transform(frame);
+                self.draw_trails(frame, &visible);
+            });
+        }
+    }
+
+    // The fates, the heatmap, the coming generations and the grid lines,
+    // over the cells.
+    fn draw_above(&self, frame: &mut impl Surface) {
+        let visible = self.cells_in(frame.size());
+        let level = self.level();
+
+        frame.with_save(|frame| {
+            self.transform(frame);
+
+            if level == 0 && self.show_fates {
+                self.draw_fates(frame, &visible);
+            }
+
+            if level == 0 && self.show_heatmap {
+                self.draw_heatmap(frame, &visible);
+            }
+
+            if level == 0 {
+                self.draw_future(frame, &visible);
+            }
+
+            if self.show_lines {
+                Self::draw_lines(frame, &visible, Cell::SIZE as f32 * self.scaling, self.theme.lines);
+            }
+        });
+    }
+
+    // Recently dead cells, fading with the generations since they died.
+    fn draw_trails(&self, frame: &mut impl Surface, visible: &Selection) {
+        let length = self.trail.length as usize;
+
+        for (age, cells) in self.trails.visible(visible, self.generation, length) {
+            let rectangles = cells
+                .iter()
+                .map(|cell| (Point::new(cell.j as f32, cell.i as f32), Size::UNIT))
+                .collect();
+
+            let color = Color {
+                a: self.theme.trail.a * self.trail.fade.alpha(age, length),
+                ..self.theme.trail
+            };
+
+            frame.fill(&Shape::Rectangles(rectangles), color);
+        }
+    }
+
+    // The coming generations, fainter the further ahead they are.
+    fn draw_future(&self, frame: &mut impl Surface, visible: &Selection) {
+        let mut future = self.future.borrow_mut();
+        let generations = future.generations(&self.life, self.revision, *visible);
+
+        for (index, cells) in generations.iter().enumerate() {
+            let rectangles = cells
+                .iter()
+                .map(|cell| (Point::new(cell.j as f32, cell.i as f32), Size::UNIT))
+                .collect();
+
+            let color = Color {
+                a: self.theme.preview.a * 0.6 * (1.0 - index as f32 / generations.len() as f32),
+                ..self.theme.preview
+            };
+
+            frame.fill(&Shape::Rectangles(rectangles), color);
+        }
+    }
+
+    // Each cell colored by what it will be in the next generation.
+    fn draw_fates(&self, frame: &mut impl Surface, visible: &Selection) {
+        for (cells, color) in inspector::fates(&self.life, visible).iter().zip(self.theme.fate.iter()) {
+            let rectangles = cells
+                .iter()
+                .map(|cell| (Point::new(cell.j as f32, cell.i as f32), Size::UNIT))
+                .collect();
+
+            frame.fill(&Shape::Rectangles(rectangles), *color);
+        }
+    }
+
+    fn draw_heatmap(&self, frame: &mut impl Surface, visible: &Selection) {
+        for (level, cells) in self.heatmap.visible(visible) {
+            let rectangles = cells
+                .iter()
+                .map(|cell| (Point::new(cell.j as f32, cell.i as f32), Size::UNIT))
+                .collect();
+
+            frame.fill(&Shape::Rectangles(rectangles), heatmap::color(&self.theme.heat, level));
+        }
+    }
+
+    // Lines between cells fade in as the cells grow, with a thicker line
+    // every `MAJOR_LINE` cells. The surface is in cell units.
+    fn draw_lines(frame: &mut impl Surface, visible: &Selection, pixels: f32, color: Color) {
+        let fade = |spacing: f32| ((spacing - 4.0) / 16.0).clamp(0.0, 1.0);
+
+        let top = visible.top_left.i as f32;
+        let bottom = (visible.bottom_right.i + 1) as f32;
+        let left = visible.top_left.j as f32;
+        let right = (visible.bottom_right.j + 1) as f32;
+
+        let kinds = [
+            (1, 1.0, 0.2 * fade(pixels)),
+            (Self::MAJOR_LINE, 2.0, 0.4 * fade(pixels * Self::MAJOR_LINE as f32)),
+        ];
+
+        for &(every, width, alpha) in kinds.iter() {
+            if alpha <= 0.0 {
+                continue;
+            }
+
+            let stroke = Stroke {
+                color: Color { a: color.a * alpha, ..color },
+                width,
+                ..Stroke::default()
+            };
+
+            for i in (visible.top_left.i..=visible.bottom_right.i + 1).filter(|i| i.rem_euclid(every) == 0) {
+                frame.stroke(&Shape::Line { from: Point::new(left, i as f32), to: Point::new(right, i as f32) }, stroke);
+            }
+
+            for j in (visible.top_left.j..=visible.bottom_right.j + 1).filter(|j| j.rem_euclid(every) == 0) {
+                frame.stroke(&Shape::Line { from: Point::new(j as f32, top), to: Point::new(j as f32, bottom) }, stroke);
+            }
+        }
+    }
+
+    // Column numbers along the top edge and row numbers along the left one,
+    // spaced at least `RULER_SPACING` pixels apart.
+    fn draw_rulers(&self, frame: &mut Frame) {
+        let region = self.visible_region(frame.size());
+        let size = Cell::SIZE as f32;
+        let pixels = size * self.scaling;
+        let step = Self::ruler_step(pixels);
+
+        let band = self.theme.panel;
+        let color = self.theme.text;
+
+        frame.fill_rectangle(Point::ORIGIN, Size::new(frame.width(), Self::RULER_HEIGHT), band);
+        frame.fill_rectangle(
+            Point::new(0.0, Self::RULER_HEIGHT),
+            Size::new(Self::RULER_WIDTH, frame.height() - Self::RULER_HEIGHT),
+            band,
+        );
+
+        let first = ((region.x / size).floor() as isize).div_euclid(step) * step;
+        let mut j = first;
+
+        loop {
+            let x = (j as f32 * size - region.x) * self.scaling + pixels / 2.0;
+
+            if x > frame.width() {
+                break;
+            }
+
+            if x > Self::RULER_WIDTH {
+                frame.fill_text(canvas::Text {
+                    content: j.to_string(),
+                    position: Point::new(x, Self::RULER_HEIGHT / 2.0),
+                    color,
+                    size: 12.0,
+                    horizontal_alignment: HorizontalAlignment::Center,
+                    vertical_alignment: VerticalAlignment::Center,
+                    ..canvas::Text::default()
+                });
+            }
+
+            j += step;
+        }
+
+        let first = ((region.y / size).floor() as isize).div_euclid(step) * step;
+        let mut i = first;
+
+        loop {
+            let y = (i as f32 * size - region.y) * self.scaling + pixels / 2.0;
+
+            if y > frame.height() {
+                break;
+            }
+
+            if y > Self::RULER_HEIGHT {
+                frame.fill_text(canvas::Text {
+                    content: i.to_string(),
+                    position: Point::new(Self::RULER_WIDTH - 4.0, y),
+                    color,
+                    size: 12.0,
+                    horizontal_alignment: HorizontalAlignment::Right,
+                    vertical_alignment: VerticalAlignment::Center,
+                    ..canvas::Text::default()
+                });
+            }
+
+            i += step;
+        }
+    }
+
+    // The smallest of 1, 2, 5, 10, 20, 50... cells that keeps ruler labels
+    // apart.
+    fn ruler_step(pixels: f32) -> isize {
+        let mut scale: isize = 1;
+
+        loop {
+            for factor in &[1, 2, 5] {
+                if (scale * factor) as f32 * pixels >= Self::RULER_SPACING {
+                    return scale * factor;
+                }
+            }
+
+            match scale.checked_mul(10) {
+                Some(next) if next < isize::MAX / 5 => scale = next,
+                _ => return scale,
+            }
+        }
+    }
+
+    fn draw_status(&self, frame: &mut Frame, hovered: Option<Cell>) {
+        let cell = match hovered {
+            Some(cell) => format!("({}, {})", cell.i, cell.j),
+            None => String::from("—"),
+        };
+
+        frame.fill_rectangle(
+            Point::new(0.0, frame.height() - Self::STATUS_HEIGHT),
+            Size::new(frame.width(), Self::STATUS_HEIGHT),
+            self.theme.panel,
+        );
+
+        frame.fill_text(canvas::Text {
+            content: format!(
+                "Célula (i, j): {}    Generación: {}    Población: {}    Regla: {}",
+                cell,
+                self.generation,
+                self.life.cells.len(),
+                Life::RULE,
+            ),
+            position: Point::new(8.0, frame.height() - Self::STATUS_HEIGHT / 2.0),
+            color: self.theme.text,
+            size: 14.0,
+            vertical_alignment: VerticalAlignment::Center,
+            ..canvas::Text::default()
+        });
+    }
+
+    fn snapshot(&self, width: u32, height: u32) -> Pixmap {
+        let mut pixmap = Pixmap::new(width, height);
+
+        self.draw_life(&mut pixmap);
+        pixmap
+    }
+
+    // Exports in the colors of the active theme, with grid lines if shown.
+    fn svg_options(&self) -> svg::Options {
+        svg::Options {
+            background: self.theme.background,
+            cell: self.theme.cell,
+            grid: if self.show_lines { Some(self.theme.lines) } else { None },
+            ..svg::Options::default()
+        }
+    }
+
+    // The size of the view is only known once the canvas has received an
+    // event, so until then the view exports the whole pattern.
+    fn to_svg(&self, extent: svg::Extent, options: &svg::Options) -> String {
+        let extent = if self.size.width < 1.0 || self.size.height < 1.0 {
+            svg::Extent::Pattern
+        } else {
+            extent
+        };
+
+        let (origin, rows, columns) = match extent {
+            svg::Extent::Pattern => match self.life.bounding_box() {
+                Some((top_left, bottom_right)) => (
+                    top_left,
+                    (bottom_right.i - top_left.i + 1) as usize,
+                    (bottom_right.j - top_left.j + 1) as usize,
+                ),
+                None => (Cell { i: 0, j: 0 }, 0, 0),
+            },
+            svg::Extent::View => {
+                let visible = self.visible_cells();
+
+                (visible.top_left, visible.rows() as usize, visible.columns() as usize)
+            },
+        };
+
+        svg::render(&self.life, origin, rows, columns, options)
+    }
+
     fn populate(&mut self, cell: Cell) {
+        if !self.life.contains(&cell) {
+            self.history.born(cell);
+        }
+
         self.life.populate(cell);
-        self.life_cache.clear();
+        self.revision += 1;
+        self.ages.changed(cell, true, self.generation);
+        self.tiles.invalidate(&cell);
+        self.redraw();
     }
 
     fn unpopulate(&mut self, cell: &Cell) {
+        if self.life.contains(cell) {
+            self.history.died(*cell);
+        }
+
         self.life.unpopulate(cell);
-        self.life_cache.clear();
+        self.revision += 1;
+        self.ages.changed(*cell, false, self.generation);
+        self.tiles.invalidate(cell);
+        self.redraw();
+    }
+
+    fn draw(&mut self, cells: Vec<Cell>) {
+        self.history.begin();
+
+        for cell in cells {
+            self.populate(cell);
+        }
+
+        self.history.end();
+    }
+
+    fn erase(&mut self, cells: Vec<Cell>) {
+        self.history.begin();
+
+        for cell in cells {
+            self.unpopulate(&cell);
+        }
+
+        self.history.end();
     }
 }
 
 impl Default for Grid {
     fn default() -> Self {
-        Self::from_preset(Preset::GliderGun)
+        Self::from_preset(Preset::find(preset::GOSPER_GLIDER_GUN).expect("the catalog has the Gosper gun"))
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 struct Life {
     cells: HashSet<Cell>
 }
 
 impl Life {
-    fn tick(&mut self) {
+    const RULE: &'static str = "B3/S23";
+
+    // Advances one generation and returns the cells that were born or died.
+    fn tick(&mut self) -> Vec<Cell> {
+        let mut changed = Vec::new();
+
+        for (cell, amount) in self.census() {
+            let is_alive = self.cells.contains(&cell);
+
+            if Life::is_alive_next(is_alive, amount) != is_alive {
+                if is_alive {
+                    self.cells.remove(&cell);
+                } else {
+                    self.cells.insert(cell);
+                }
+
+                changed.push(cell);
+            }
+        }
+
+        changed
+    }
+
+    // The live neighbors of every live cell and of every cell next to one.
+    fn census(&self) -> HashMap<Cell, usize> {
         let mut adjacent_life: HashMap<Cell,usize> = HashMap::default();
 
         for cell in &self.cells {
@@ -381,23 +2078,53 @@ impl Life {
             }
         }
 
-        for (cell, amount) in adjacent_life.iter() {
-            match amount {
-                2 => {},
-                3 => {
-                    self.cells.insert(*cell);
-                },
-                _ => {
-                    self.cells.remove(cell);
-                }
-            }
-        }
+        adjacent_life
+    }
+
+    fn neighbors_of(&self, cell: Cell) -> usize {
+        Cell::neighbors(cell).filter(|neighbour| self.cells.contains(neighbour)).count()
+    }
+
+    fn is_alive_next(is_alive: bool, amount: usize) -> bool {
+        amount == 3 || is_alive && amount == 2
+    }
+
+    fn bounding_box(&self) -> Option<(Cell, Cell)> {
+        let top = self.cells.iter().map(|cell| cell.i).min()?;
+        let bottom = self.cells.iter().map(|cell| cell.i).max()?;
+        let left = self.cells.iter().map(|cell| cell.j).min()?;
+        let right = self.cells.iter().map(|cell| cell.j).max()?;
+
+        Some((Cell { i: top, j: left }, Cell { i: bottom, j: right }))
     }
 
     fn contains(&self, cell: &Cell) -> bool {
         self.cells.contains(cell)
     }
 
+    // The live cells inside `area`. Small areas are probed cell by cell and
+    // large ones filter the population, so the cost is bounded by whichever
+    // is smaller.
+    fn within(&self, area: &Selection) -> Vec<Cell> {
+        let size = area.rows().checked_mul(area.columns()).unwrap_or(isize::MAX);
+
+        if (size as usize) < self.cells.len() {
+            let mut cells = Vec::new();
+
+            for i in area.top_left.i..=area.bottom_right.i {
+                for j in area.top_left.j..=area.bottom_right.j {
+                    if self.cells.contains(&Cell { i, j }) {
+                        cells.push(Cell { i, j });
+                    }
+                }
+            }
+
+            cells
+        } else {
+            self.cells.iter().filter(|cell| area.contains(cell)).copied().collect()
+        }
+    }
+
     fn populate(&mut self, cell: Cell) {
         self.cells.insert(cell);
     }
@@ -448,56 +2175,135 @@ impl Cell {
     }
 }
 
-enum Preset {
-    Glider,
-    GliderGun,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl Preset {
-    pub fn life(self) -> Vec<(isize, isize)> {
-
-        #[rustfmt::skip]
-        let cells = match self {
-            Preset::Glider => vec![
-                " x ",
-                "  x",
-                "xxx"
-            ],
-            Preset::GliderGun => vec![
-                "                        x           ",
-                "                      x x           ",
-                "            xx      xx            xx",
-                "           x   x    xx            xx",
-                "xx        x     x   xx              ",
-                "xx        x   x xx    x x           ",
-                "          x     x       x           ",
-                "           x   x                    ",
-                "            xx                      ",
-            ],
-        };
+    fn life(cells: &[(isize, isize)]) -> Life {
+        cells.iter().map(|&(i, j)| Cell { i, j }).collect()
+    }
 
-        let start_row = -(cells.len() as isize / 2);
+    #[test]
+    fn blinker_has_period_two() {
+        let start = life(&[(0, -1), (0, 0), (0, 1)]);
+        let mut blinker = start.clone();
 
-        cells
-            .into_iter()
-            .enumerate()
-            .flat_map(|(i, cells)| {
-                let start_column = -(cells.len() as isize / 2);
+        blinker.tick();
+        assert_eq!(blinker.cells, life(&[(-1, 0), (0, 0), (1, 0)]).cells);
 
-                cells
-                    .chars()
-                    .enumerate()
-                    .filter(|(_, c)| !c.is_whitespace())
-                    .map(move |(j, _)| {
-                        (start_row + i as isize, start_column + j as isize)
-                    })
-            }).collect()
+        blinker.tick();
+        assert_eq!(blinker.cells, start.cells);
     }
-}
 
-impl Default for Preset {
-    fn default() -> Preset {
-        Preset::Glider
+    #[test]
+    fn block_is_still() {
+        let block = life(&[(0, 0), (0, 1), (1, 0), (1, 1)]);
+        let mut next = block.clone();
+
+        assert!(next.tick().is_empty());
+        assert_eq!(next.cells, block.cells);
     }
-}
 
+    #[test]
+    fn glider_moves_diagonally() {
+        let glider = life(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        let mut next = glider.clone();
+
+        for _ in 0..4 {
+            next.tick();
+        }
+
+        let moved: Life = glider.cells.iter().map(|cell| Cell { i: cell.i + 1, j: cell.j + 1 }).collect();
+
+        assert_eq!(next.cells, moved.cells);
+    }
+
+    #[test]
+    fn parses_recorder_options() {
+        let options = vec![String::from("scale=4"), String::from("delay=2"), String::from("xq4_153")];
+        let (recorder, code) = parse_recorder("10", "25", &options).unwrap();
+
+        assert_eq!(recorder.generations, 10..25);
+        assert_eq!((recorder.scale, recorder.delay), (4, 2));
+        assert_eq!(code, Some("xq4_153"));
+    }
+
+    #[test]
+    fn rejects_empty_ranges_and_zero_scale() {
+        let none: Vec<String> = Vec::new();
+        let option = |option: &str| vec![String::from(option)];
+
+        assert!(parse_recorder("10", "10", &none).is_err());
+        assert!(parse_recorder("10", "5", &none).is_err());
+        assert!(parse_recorder("-1", "5", &none).is_err());
+        assert!(parse_recorder("0", "ten", &none).is_err());
+        assert!(parse_recorder("0", "5", &option("scale=0")).is_err());
+        assert!(parse_recorder("0", "5", &option("crop=0,0,0,5")).is_err());
+        assert!(parse_recorder("0", "5", &option("speed=2")).is_err());
+    }
+
+    #[test]
+    fn exports_the_pattern_until_the_view_has_a_size() {
+        let glider = vec![Cell { i: 0, j: 1 }, Cell { i: 1, j: 2 }, Cell { i: 2, j: 0 }, Cell { i: 2, j: 1 }, Cell { i: 2, j: 2 }];
+        let mut grid = Grid::from_cells(glider);
+        let options = svg::Options::default();
+
+        let pattern = grid.to_svg(svg::Extent::Pattern, &options);
+
+        assert!(pattern.contains(r#"width="30" height="30""#));
+        assert_eq!(grid.to_svg(svg::Extent::View, &options), pattern);
+
+        grid.size = Size::new(200.0, 100.0);
+
+        assert!(grid.to_svg(svg::Extent::View, &options).contains(r#"width="210" height="110""#));
+    }
+
+    #[test]
+    fn fits_the_pattern_in_the_view() {
+        let mut grid = Grid::from_cells(vec![Cell { i: 0, j: 0 }, Cell { i: 999, j: 1999 }]);
+
+        grid.size = Size::new(200.0, 100.0);
+        grid.fit();
+
+        let (top_left, _) = grid.life.bounding_box().unwrap();
+        let (translation, scaling) = grid.camera();
+
+        assert_eq!(translation, Vector::new(-(top_left.j as f32 * 10.0 + 10_000.0), -(top_left.i as f32 * 10.0 + 5_000.0)));
+        assert!((scaling - 0.009).abs() < 1e-6);
+
+        let mut glider = Grid::from_cells(vec![Cell { i: 0, j: 1 }, Cell { i: 1, j: 2 }, Cell { i: 2, j: 0 }, Cell { i: 2, j: 1 }, Cell { i: 2, j: 2 }]);
+
+        glider.size = Size::new(600.0, 600.0);
+        glider.fit();
+
+        assert_eq!(glider.camera(), (Vector::new(-5.0, -5.0), Limits::default().max));
+    }
+
+    #[test]
+    fn goes_to_a_coordinate() {
+        let mut grid = Grid::from_cells(Vec::new());
+
+        grid.center_on(camera::parse_coordinate("3, -7").unwrap());
+
+        assert_eq!(grid.camera().0, Vector::new(-35.0, 65.0));
+
+        grid.fit();
+
+        assert_eq!(grid.camera().0, Vector::new(-35.0, 65.0));
+    }
+
+    #[test]
+    fn census_counts_live_neighbors() {
+        let pair = life(&[(0, 0), (0, 1)]);
+        let census = pair.census();
+
+        assert_eq!(census[&Cell { i: 0, j: 0 }], 1);
+        assert_eq!(census[&Cell { i: 1, j: 0 }], 2);
+        assert_eq!(census[&Cell { i: -1, j: 2 }], 1);
+        assert_eq!(census.len(), 12);
+
+        for (cell, neighbors) in census {
+            assert_eq!(neighbors, pair.neighbors_of(cell));
+        }
+    }
+}
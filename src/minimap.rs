@@ -0,0 +1,203 @@
+use iced::canvas::event::{self, Event};
+use iced::canvas::{self, Cache, Canvas, Cursor, Geometry, Path, Stroke};
+use iced::{mouse, Color, Element, Length, Point, Rectangle, Size};
+
+use crate::theme::Theme;
+use crate::{Cell, Life, Message};
+
+// An overview of the whole pattern with the visible part of the grid
+// outlined. Clicking or dragging centers the view on that point.
+pub struct Minimap {
+    cache: Cache,
+    // The square area around the pattern, in cell units.
+    area: Rectangle,
+    counts: Vec<u32>,
+    revision: Option<usize>,
+    view: Rectangle,
+    is_dragging: bool,
+    background: Color,
+    cell: Color,
+    outline: Color,
+}
+
+impl Minimap {
+    pub const SIZE: u16 = 160;
+    const RESOLUTION: usize = 80;
+    const MARGIN: f32 = 0.1;
+
+    pub fn new(theme: &Theme) -> Self {
+        let mut minimap = Minimap {
+            cache: Cache::default(),
+            area: Rectangle::new(Point::ORIGIN, Size::UNIT),
+            counts: vec![0; Self::RESOLUTION * Self::RESOLUTION],
+            revision: None,
+            view: Rectangle::new(Point::ORIGIN, Size::UNIT),
+            is_dragging: false,
+            background: Color::BLACK,
+            cell: Color::WHITE,
+            outline: Color::WHITE,
+        };
+
+        minimap.set_theme(theme);
+        minimap
+    }
+
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.background = theme.background;
+        self.cell = theme.cell;
+        self.outline = Color { a: 1.0, ..theme.preview };
+        self.cache.clear();
+    }
+
+    // Counts the live cells falling in each pixel of the overview, unless
+    // `revision` of the universe was already counted.
+    pub fn set_life(&mut self, life: &Life, revision: usize) {
+        if self.revision == Some(revision) {
+            return;
+        }
+
+        self.revision = Some(revision);
+
+        let (top_left, bottom_right) = match life.bounding_box() {
+            Some(bounds) => bounds,
+            None => (Cell { i: 0, j: 0 }, Cell { i: 0, j: 0 }),
+        };
+
+        let width = (bottom_right.j - top_left.j + 1) as f32;
+        let height = (bottom_right.i - top_left.i + 1) as f32;
+        let side = width.max(height) * (1.0 + 2.0 * Self::MARGIN);
+
+        self.area = Rectangle {
+            x: top_left.j as f32 + width / 2.0 - side / 2.0,
+            y: top_left.i as f32 + height / 2.0 - side / 2.0,
+            width: side,
+            height: side,
+        };
+
+        self.counts = vec![0; Self::RESOLUTION * Self::RESOLUTION];
+
+        let scale = Self::RESOLUTION as f32 / side;
+
+        for cell in &life.cells {
+            let column = ((cell.j as f32 + 0.5 - self.area.x) * scale) as usize;
+            let row = ((cell.i as f32 + 0.5 - self.area.y) * scale) as usize;
+
+            if row < Self::RESOLUTION && column < Self::RESOLUTION {
+                self.counts[row * Self::RESOLUTION + column] += 1;
+            }
+        }
+
+        self.cache.clear();
+    }
+
+    // The visible region of the grid, in cell units.
+    pub fn set_view(&mut self, view: Rectangle) {
+        if view != self.view {
+            self.view = view;
+            self.cache.clear();
+        }
+    }
+
+    pub fn view(&mut self) -> Element<Message> {
+        Canvas::new(self)
+            .width(Length::Units(Self::SIZE))
+            .height(Length::Units(Self::SIZE))
+            .into()
+    }
+
+    // The area around the pattern, grown into a square that also holds the
+    // view, so the view stays visible when it is far from the pattern.
+    fn shown(&self) -> Rectangle {
+        let left = self.area.x.min(self.view.x);
+        let top = self.area.y.min(self.view.y);
+        let right = (self.area.x + self.area.width).max(self.view.x + self.view.width);
+        let bottom = (self.area.y + self.area.height).max(self.view.y + self.view.height);
+        let side = (right - left).max(bottom - top);
+
+        Rectangle {
+            x: (left + right - side) / 2.0,
+            y: (top + bottom - side) / 2.0,
+            width: side,
+            height: side,
+        }
+    }
+
+    fn to_cells(&self, position: Point, size: Size) -> Point {
+        let shown = self.shown();
+
+        Point::new(
+            shown.x + position.x / size.width * shown.width,
+            shown.y + position.y / size.height * shown.height,
+        )
+    }
+}
+
+impl canvas::Program<Message> for Minimap {
+    fn update(&mut self, event: Event, bounds: Rectangle, cursor: Cursor) -> (event::Status, Option<Message>) {
+        if let Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) = event {
+            self.is_dragging = false;
+        }
+
+        let position = match cursor.position_in(&bounds) {
+            Some(position) => position,
+            None => return (event::Status::Ignored, None),
+        };
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                self.is_dragging = true;
+            },
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if self.is_dragging => {},
+            _ => return (event::Status::Ignored, None),
+        }
+
+        let center = self.to_cells(position, bounds.size());
+
+        (event::Status::Captured, Some(Message::MoveView(center)))
+    }
+
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let minimap = self.cache.draw(bounds.size(), |frame| {
+            frame.fill_rectangle(Point::ORIGIN, frame.size(), self.background);
+
+            if frame.width() < 2.0 || frame.height() < 2.0 {
+                return;
+            }
+
+            let shown = self.shown();
+            let scale = frame.width() / shown.width;
+            let pixel = self.area.width / Self::RESOLUTION as f32 * scale;
+            let origin = Point::new((self.area.x - shown.x) * scale, (self.area.y - shown.y) * scale);
+            let max = self.counts.iter().copied().max().unwrap_or(0).max(1) as f32;
+
+            for (index, count) in self.counts.iter().enumerate().filter(|(_, count)| **count > 0) {
+                let (row, column) = (index / Self::RESOLUTION, index % Self::RESOLUTION);
+
+                frame.fill_rectangle(
+                    Point::new(origin.x + column as f32 * pixel, origin.y + row as f32 * pixel),
+                    Size::new(pixel, pixel),
+                    Color {
+                        a: self.cell.a * (0.4 + 0.6 * *count as f32 / max),
+                        ..self.cell
+                    },
+                );
+            }
+
+            let size = Size::new((self.view.width * scale).max(1.0), (self.view.height * scale).max(1.0));
+
+            frame.stroke(
+                &Path::rectangle(
+                    Point::new((self.view.x - shown.x) * scale, (self.view.y - shown.y) * scale),
+                    size,
+                ),
+                Stroke {
+                    color: self.outline,
+                    width: 1.5,
+                    ..Stroke::default()
+                },
+            );
+        });
+
+        vec![minimap]
+    }
+}
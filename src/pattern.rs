@@ -0,0 +1,450 @@
+use std::fmt;
+
+use crate::apgcode;
+use crate::{Cell, Life};
+
+const LINE_LENGTH: usize = 70;
+const MAX_LEVEL: usize = 62;
+// Patterns are expanded into a list of cells, so a few characters of RLE must
+// not be able to describe more cells than fit in memory.
+const MAX_CELLS: usize = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Empty,
+    UnexpectedCharacter(char),
+    InvalidNode(String),
+    TooLarge,
+    Apgcode(apgcode::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Empty => write!(f, "the pattern has no live cells"),
+            Error::UnexpectedCharacter(c) => write!(f, "unexpected character `{}` in pattern", c),
+            Error::InvalidNode(line) => write!(f, "invalid macrocell node `{}`", line),
+            Error::TooLarge => write!(f, "the pattern has more than {} live cells or does not fit in the grid", MAX_CELLS),
+            Error::Apgcode(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<apgcode::Error> for Error {
+    fn from(error: apgcode::Error) -> Self {
+        Error::Apgcode(error)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+}
+
+// Parses RLE, plaintext `.cells`, macrocell or an apgcode, guessing the format
+// from the text. The cells are returned relative to the pattern's top left
+// corner.
+pub fn parse(text: &str) -> Result<Vec<Cell>, Error> {
+    let text = text.trim();
+
+    let cells = if is_apgcode(text) {
+        apgcode::decode(text)?
+    } else if is_macrocell(text) {
+        parse_macrocell(text)?
+    } else if is_rle(text) {
+        parse_rle(text)?
+    } else {
+        parse_cells(text)?
+    };
+
+    if cells.is_empty() {
+        return Err(Error::Empty);
+    }
+
+    Ok(normalize(cells))
+}
+
+pub fn parse_rle(text: &str) -> Result<Vec<Cell>, Error> {
+    let mut cells = Vec::new();
+    let (mut i, mut j): (isize, isize) = (0, 0);
+    let mut count: Option<isize> = None;
+
+    let body = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#') && !is_rle_header(line));
+
+    'lines: for line in body {
+        for c in line.chars() {
+            match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).unwrap_or(0) as isize;
+
+                    count = count
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|count| count.checked_add(digit));
+
+                    if count.is_none() {
+                        return Err(Error::TooLarge);
+                    }
+
+                    continue;
+                },
+                'b' | '.' => j = j.checked_add(count.unwrap_or(1)).ok_or(Error::TooLarge)?,
+                '$' => {
+                    i = i.checked_add(count.unwrap_or(1)).ok_or(Error::TooLarge)?;
+                    j = 0;
+                },
+                '!' => break 'lines,
+                c if c.is_ascii_alphabetic() => {
+                    let run = count.unwrap_or(1);
+
+                    if j.checked_add(run).is_none() || cells.len() + run as usize > MAX_CELLS {
+                        return Err(Error::TooLarge);
+                    }
+
+                    for _ in 0..run {
+                        cells.push(Cell { i, j });
+                        j += 1;
+                    }
+                },
+                c if c.is_whitespace() => continue,
+                _ => return Err(Error::UnexpectedCharacter(c)),
+            }
+
+            count = None;
+        }
+    }
+
+    Ok(cells)
+}
+
+pub fn parse_cells(text: &str) -> Result<Vec<Cell>, Error> {
+    let mut cells = Vec::new();
+
+    let rows = text.lines().filter(|line| !line.starts_with('!'));
+
+    for (i, row) in rows.enumerate() {
+        for (j, c) in row.trim_end().chars().enumerate() {
+            match c {
+                'O' | 'o' | '*' => cells.push(Cell { i: i as isize, j: j as isize }),
+                '.' | ' ' => {},
+                _ => return Err(Error::UnexpectedCharacter(c)),
+            }
+        }
+    }
+
+    Ok(cells)
+}
+
+// Golly's macrocell format: 8x8 leaves written as `.`, `*` and `$`, followed
+// by `level nw ne sw se` nodes that refer to earlier lines, 0 being empty.
+// Levels above `MAX_LEVEL` would overflow the cell coordinates. Nodes can be
+// shared, so a short file can describe far more cells than `MAX_CELLS`; the
+// live cells of every node are counted before anything is expanded.
+pub fn parse_macrocell(text: &str) -> Result<Vec<Cell>, Error> {
+    enum Node {
+        Leaf(Vec<(isize, isize)>),
+        Branch(u32, [usize; 4]),
+    }
+
+    let mut nodes = vec![Node::Leaf(Vec::new())];
+    let mut population: Vec<usize> = vec![0];
+
+    let lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('[') && !line.starts_with('#'));
+
+    for line in lines {
+        if line.starts_with(['.', '*', '$']) {
+            let mut cells = Vec::new();
+
+            for (i, row) in line.split('$').enumerate() {
+                for (j, c) in row.chars().enumerate() {
+                    match c {
+                        '*' => cells.push((i as isize, j as isize)),
+                        '.' => {},
+                        _ => return Err(Error::UnexpectedCharacter(c)),
+                    }
+                }
+            }
+
+            population.push(cells.len());
+            nodes.push(Node::Leaf(cells));
+        } else {
+            let invalid = || Error::InvalidNode(String::from(line));
+
+            let values = line
+                .split_whitespace()
+                .map(|value| value.parse::<usize>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid())?;
+
+            match values.as_slice() {
+                [level, children @ ..] if children.len() == 4
+                    && *level > 3
+                    && *level <= MAX_LEVEL
+                    && children.iter().all(|child| *child < nodes.len()) =>
+                {
+                    let count = children
+                        .iter()
+                        .map(|child| population[*child])
+                        .try_fold(0usize, usize::checked_add);
+
+                    population.push(count.unwrap_or(usize::MAX));
+                    nodes.push(Node::Branch(*level as u32, [children[0], children[1], children[2], children[3]]));
+                },
+                _ => return Err(invalid()),
+            }
+        }
+    }
+
+    fn expand(nodes: &[Node], index: usize, i: isize, j: isize, cells: &mut Vec<Cell>) {
+        match &nodes[index] {
+            Node::Leaf(leaf) => cells.extend(
+                leaf.iter().map(|(row, column)| Cell { i: i + row, j: j + column })
+            ),
+            Node::Branch(level, children) => {
+                let half = 1isize << (level - 1);
+
+                for (child, (row, column)) in children.iter().zip(&[(0, 0), (0, 1), (1, 0), (1, 1)]) {
+                    if *child != 0 {
+                        expand(nodes, *child, i + row * half, j + column * half, cells);
+                    }
+                }
+            },
+        }
+    }
+
+    if population.last().is_some_and(|count| *count > MAX_CELLS) {
+        return Err(Error::TooLarge);
+    }
+
+    let mut cells = Vec::new();
+
+    if nodes.len() > 1 {
+        expand(&nodes, nodes.len() - 1, 0, 0, &mut cells);
+    }
+
+    Ok(cells)
+}
+
+// Reads the name, author and comments from RLE (`#N`, `#O`, `#C`), macrocell
+// (same lines) or plaintext (`!Name:`, `!Author:`, `!`) headers.
+pub fn metadata(text: &str) -> Metadata {
+    let mut metadata = Metadata::default();
+
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("#N").or_else(|| line.strip_prefix("!Name:")) {
+            metadata.name = Some(String::from(name.trim()));
+        } else if let Some(author) = line.strip_prefix("#O").or_else(|| line.strip_prefix("!Author:")) {
+            metadata.author = Some(String::from(author.trim()));
+        } else if let Some(comment) = line
+            .strip_prefix("#C")
+            .or_else(|| line.strip_prefix("#c"))
+            .or_else(|| line.strip_prefix('!'))
+        {
+            if !comment.trim().is_empty() {
+                metadata.comments.push(String::from(comment.trim()));
+            }
+        }
+    }
+
+    metadata
+}
+
+pub fn to_rle<'a>(cells: impl IntoIterator<Item = &'a Cell>) -> String {
+    let mut cells: Vec<Cell> = normalize(cells.into_iter().copied().collect());
+    cells.sort_by_key(|cell| (cell.i, cell.j));
+
+    let width = cells.iter().map(|cell| cell.j + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|cell| cell.i + 1).max().unwrap_or(0);
+
+    let mut runs: Vec<(usize, char)> = Vec::new();
+    let mut push = |length: isize, tag: char| {
+        if length <= 0 {
+            return;
+        }
+
+        match runs.last_mut() {
+            Some((run, last)) if *last == tag => *run += length as usize,
+            _ => runs.push((length as usize, tag)),
+        }
+    };
+
+    let (mut i, mut j) = (0, 0);
+
+    for cell in &cells {
+        if cell.i > i {
+            push(cell.i - i, '$');
+            i = cell.i;
+            j = 0;
+        }
+
+        push(cell.j - j, 'b');
+        push(1, 'o');
+        j = cell.j + 1;
+    }
+
+    let mut rle = format!("x = {}, y = {}, rule = {}\n", width, height, Life::RULE);
+    let mut line = String::new();
+
+    let tokens = runs
+        .into_iter()
+        .map(|(length, tag)| if length == 1 {
+            tag.to_string()
+        } else {
+            format!("{}{}", length, tag)
+        })
+        .chain(std::iter::once(String::from("!")));
+
+    for token in tokens {
+        if line.len() + token.len() > LINE_LENGTH {
+            rle.push_str(&line);
+            rle.push('\n');
+            line.clear();
+        }
+
+        line.push_str(&token);
+    }
+
+    rle.push_str(&line);
+    rle.push('\n');
+    rle
+}
+
+pub fn normalize(mut cells: Vec<Cell>) -> Vec<Cell> {
+    let top = cells.iter().map(|cell| cell.i).min().unwrap_or(0);
+    let left = cells.iter().map(|cell| cell.j).min().unwrap_or(0);
+
+    for cell in cells.iter_mut() {
+        cell.i -= top;
+        cell.j -= left;
+    }
+
+    cells
+}
+
+fn is_apgcode(text: &str) -> bool {
+    text.starts_with('x')
+        && text.contains('_')
+        && !text.chars().any(char::is_whitespace)
+}
+
+fn is_macrocell(text: &str) -> bool {
+    text.starts_with("[M2]")
+}
+
+fn is_rle(text: &str) -> bool {
+    text.lines().map(str::trim).any(is_rle_header)
+        || text.trim_end().ends_with('!')
+}
+
+fn is_rle_header(line: &str) -> bool {
+    line.starts_with('x') && line.contains('=')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: [(isize, isize); 5] = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+
+    fn sorted(cells: Vec<Cell>) -> Vec<(isize, isize)> {
+        let mut cells: Vec<(isize, isize)> = cells.iter().map(|cell| (cell.i, cell.j)).collect();
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn parses_rle() {
+        let text = "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+
+        assert_eq!(sorted(parse(text).unwrap()), GLIDER);
+        assert_eq!(sorted(parse_rle("2o$\n2o!").unwrap()), vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(sorted(parse_rle("o2$o!").unwrap()), vec![(0, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn parses_plaintext() {
+        let text = "!Name: Glider\n.O\n..O\nOOO";
+
+        assert_eq!(sorted(parse(text).unwrap()), GLIDER);
+    }
+
+    #[test]
+    fn parses_macrocell() {
+        let text = "[M2] (golly 4.0)\n#R B3/S23\n$*$.*\n4 1 0 0 1";
+
+        assert_eq!(sorted(parse(text).unwrap()), vec![(0, 0), (1, 1), (8, 8), (9, 9)]);
+    }
+
+    #[test]
+    fn parses_apgcode() {
+        assert_eq!(sorted(parse("xq4_153").unwrap()).len(), 5);
+    }
+
+    #[test]
+    fn round_trips_rle() {
+        let cells: Vec<Cell> = GLIDER.iter().map(|&(i, j)| Cell { i, j }).collect();
+
+        assert_eq!(sorted(parse(&to_rle(&cells)).unwrap()), GLIDER);
+    }
+
+    #[test]
+    fn reads_metadata() {
+        let metadata = metadata("#N Glider\n#O Richard K. Guy\n#C The smallest spaceship.\nbo$2bo$3o!");
+
+        assert_eq!(metadata.name.as_deref(), Some("Glider"));
+        assert_eq!(metadata.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(metadata.comments, vec![String::from("The smallest spaceship.")]);
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(parse("x = 3, y = 3\nbo$2b?o!"), Err(Error::UnexpectedCharacter('?')));
+        assert_eq!(parse(".O\n.X"), Err(Error::UnexpectedCharacter('X')));
+        assert_eq!(parse("[M2]\n4 1 2 3"), Err(Error::InvalidNode(String::from("4 1 2 3"))));
+        assert_eq!(parse("[M2]\n$*$\n4 9 0 0 0"), Err(Error::InvalidNode(String::from("4 9 0 0 0"))));
+        assert_eq!(parse("[M2]\n$*$\n63 1 0 0 1"), Err(Error::InvalidNode(String::from("63 1 0 0 1"))));
+        assert_eq!(parse(&shared_macrocell(20)), Err(Error::TooLarge));
+        assert_eq!(parse("xs4_3!"), Err(Error::Apgcode(apgcode::Error::InvalidCharacter('!'))));
+    }
+
+    // A full leaf and a chain of levels that each repeat the one below four
+    // times: a few lines with 64 * 4^(levels - 3) live cells.
+    fn shared_macrocell(levels: usize) -> String {
+        let mut text = String::from("[M2]\n");
+
+        text.push_str(&"********$".repeat(8));
+        text.push('\n');
+
+        for level in 4..=levels {
+            let child = level - 3;
+
+            text.push_str(&format!("{} {} {} {} {}\n", level, child, child, child, child));
+        }
+
+        text
+    }
+
+    #[test]
+    fn counts_shared_macrocell_nodes() {
+        assert_eq!(parse_macrocell(&shared_macrocell(5)).map(|cells| cells.len()), Ok(1024));
+        assert_eq!(parse_macrocell(&shared_macrocell(60)), Err(Error::TooLarge));
+    }
+
+    #[test]
+    fn rejects_oversized_rle() {
+        assert_eq!(parse("99999999999999999999999o!"), Err(Error::TooLarge));
+        assert_eq!(parse_rle(&format!("{}o!", MAX_CELLS + 1)), Err(Error::TooLarge));
+        assert_eq!(parse_rle(&format!("{0}o$o${0}o!", MAX_CELLS / 2)), Err(Error::TooLarge));
+        assert_eq!(parse_rle("5000000000000000000b5000000000000000000bo!"), Err(Error::TooLarge));
+        assert_eq!(parse_rle(&format!("{}o!", MAX_CELLS)).map(|cells| cells.len()), Ok(MAX_CELLS));
+    }
+}
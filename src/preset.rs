@@ -0,0 +1,528 @@
+use crate::pattern;
+use crate::Cell;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    Methuselah,
+    Gun,
+    Puffer,
+}
+
+impl Category {
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::StillLife => "Vida estática",
+            Category::Oscillator => "Oscilador",
+            Category::Spaceship => "Nave",
+            Category::Methuselah => "Matusalén",
+            Category::Gun => "Cañón",
+            Category::Puffer => "Puffer",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Preset {
+    pub name: &'static str,
+    pub category: Category,
+    pub author: Option<&'static str>,
+    pub period: Option<usize>,
+    pub speed: Option<&'static str>,
+    pub description: &'static str,
+    pattern: &'static str,
+}
+
+impl Preset {
+    pub fn cells(&self) -> Vec<Cell> {
+        pattern::parse(self.pattern).expect("presets are valid patterns")
+    }
+
+    pub fn find(name: &str) -> Option<&'static Preset> {
+        CATALOG.iter().find(|preset| preset.name == name)
+    }
+}
+
+pub const GOSPER_GLIDER_GUN: &str = "Gosper glider gun";
+
+pub static CATALOG: &[Preset] = &[
+    Preset {
+        name: "Block",
+        category: Category::StillLife,
+        author: None,
+        period: Some(1),
+        speed: None,
+        description: "La vida estática más común: un cuadrado de 2x2.",
+        pattern: "xs4_33",
+    },
+    Preset {
+        name: "Beehive",
+        category: Category::StillLife,
+        author: None,
+        period: Some(1),
+        speed: None,
+        description: "Segunda vida estática más común, resultado frecuente de reacciones.",
+        pattern: "xs6_696",
+    },
+    Preset {
+        name: "Loaf",
+        category: Category::StillLife,
+        author: None,
+        period: Some(1),
+        speed: None,
+        description: "Vida estática de siete células con forma de hogaza.",
+        pattern: "xs7_178c",
+    },
+    Preset {
+        name: "Boat",
+        category: Category::StillLife,
+        author: None,
+        period: Some(1),
+        speed: None,
+        description: "La única vida estática de cinco células.",
+        pattern: "xs5_253",
+    },
+    Preset {
+        name: "Ship",
+        category: Category::StillLife,
+        author: None,
+        period: Some(1),
+        speed: None,
+        description: "Vida estática de seis células, un bote alargado.",
+        pattern: "xs6_356",
+    },
+    Preset {
+        name: "Tub",
+        category: Category::StillLife,
+        author: None,
+        period: Some(1),
+        speed: None,
+        description: "Cuatro células en diamante alrededor de un hueco.",
+        pattern: "xs4_252",
+    },
+    Preset {
+        name: "Pond",
+        category: Category::StillLife,
+        author: None,
+        period: Some(1),
+        speed: None,
+        description: "Anillo de ocho células.",
+        pattern: "xs8_6996",
+    },
+    Preset {
+        name: "Blinker",
+        category: Category::Oscillator,
+        author: Some("John Conway"),
+        period: Some(2),
+        speed: None,
+        description: "El oscilador más pequeño y común: tres células en línea.",
+        pattern: "xp2_7",
+    },
+    Preset {
+        name: "Toad",
+        category: Category::Oscillator,
+        author: Some("Simon Norton"),
+        period: Some(2),
+        speed: None,
+        description: "Dos filas desplazadas de tres células.",
+        pattern: "xp2_7e",
+    },
+    Preset {
+        name: "Beacon",
+        category: Category::Oscillator,
+        author: Some("John Conway"),
+        period: Some(2),
+        speed: None,
+        description: "Dos bloques en diagonal cuyas esquinas parpadean.",
+        pattern: "xp2_318c",
+    },
+    Preset {
+        name: "Clock",
+        category: Category::Oscillator,
+        author: Some("Simon Norton"),
+        period: Some(2),
+        speed: None,
+        description: "Oscilador de seis células que parece girar.",
+        pattern: "xp2_2a54",
+    },
+    Preset {
+        name: "Pulsar",
+        category: Category::Oscillator,
+        author: Some("John Conway"),
+        period: Some(3),
+        speed: None,
+        description: "El oscilador de periodo 3 más común, con simetría completa.",
+        pattern: "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401",
+    },
+    Preset {
+        name: "Figure eight",
+        category: Category::Oscillator,
+        author: Some("Simon Norton"),
+        period: Some(8),
+        speed: None,
+        description: "Dos cuadrados de 3x3 que se tocan en una esquina.",
+        pattern: "xp8_gk2gb3z11",
+    },
+    Preset {
+        name: "Kok's galaxy",
+        category: Category::Oscillator,
+        author: Some("Jan Kok"),
+        period: Some(8),
+        speed: None,
+        description: "Cuatro brazos que giran alrededor de un centro vacío.",
+        pattern: "xp8_4b23021eaz57840c4d2",
+    },
+    Preset {
+        name: "Pentadecathlon",
+        category: Category::Oscillator,
+        author: Some("John Conway"),
+        period: Some(15),
+        speed: None,
+        description: "Oscilador de periodo 15 que surge de una fila de diez células.",
+        pattern: "xp15_4r4z4r4",
+    },
+    Preset {
+        name: "Queen bee shuttle",
+        category: Category::Oscillator,
+        author: Some("Bill Gosper"),
+        period: Some(30),
+        speed: None,
+        description: "Una abeja reina que rebota entre dos bloques; es el corazón del cañón de Gosper.",
+        pattern: "xp30_w33z8kqrqk8zzzw33",
+    },
+    Preset {
+        name: "Glider",
+        category: Category::Spaceship,
+        author: Some("Richard K. Guy"),
+        period: Some(4),
+        speed: Some("c/4"),
+        description: "La nave más pequeña; viaja en diagonal.",
+        pattern: "xq4_153",
+    },
+    Preset {
+        name: "LWSS",
+        category: Category::Spaceship,
+        author: Some("John Conway"),
+        period: Some(4),
+        speed: Some("c/2"),
+        description: "Nave ligera (lightweight spaceship) que viaja en horizontal.",
+        pattern: "xq4_6frc",
+    },
+    Preset {
+        name: "MWSS",
+        category: Category::Spaceship,
+        author: Some("John Conway"),
+        period: Some(4),
+        speed: Some("c/2"),
+        description: "Nave mediana (middleweight spaceship).",
+        pattern: "xq4_27dee6",
+    },
+    Preset {
+        name: "HWSS",
+        category: Category::Spaceship,
+        author: Some("John Conway"),
+        period: Some(4),
+        speed: Some("c/2"),
+        description: "Nave pesada (heavyweight spaceship).",
+        pattern: "xq4_27deee6",
+    },
+    Preset {
+        name: "Copperhead",
+        category: Category::Spaceship,
+        author: Some("zdr"),
+        period: Some(10),
+        speed: Some("c/10"),
+        description: "Nave ortogonal lenta descubierta en 2016.",
+        pattern: "xq10_o5995ozes88sezw33",
+    },
+    Preset {
+        name: "R-pentomino",
+        category: Category::Methuselah,
+        author: Some("John Conway"),
+        period: None,
+        speed: None,
+        description: "Cinco células que tardan 1103 generaciones en estabilizarse.",
+        pattern: "b2o$2o$bo!",
+    },
+    Preset {
+        name: "Acorn",
+        category: Category::Methuselah,
+        author: Some("Charles Corderman"),
+        period: None,
+        speed: None,
+        description: "Siete células que se estabilizan tras 5206 generaciones con 633 células.",
+        pattern: "bo$3bo$2o2b3o!",
+    },
+    Preset {
+        name: "Diehard",
+        category: Category::Methuselah,
+        author: None,
+        period: None,
+        speed: None,
+        description: "Desaparece por completo tras 130 generaciones.",
+        pattern: "6bo$2o$bo3b3o!",
+    },
+    Preset {
+        name: "B-heptomino",
+        category: Category::Methuselah,
+        author: Some("John Conway"),
+        period: None,
+        speed: None,
+        description: "Heptominó que se estabiliza tras 148 generaciones.",
+        pattern: "ob2o$3o$bo!",
+    },
+    Preset {
+        name: "Pi-heptomino",
+        category: Category::Methuselah,
+        author: Some("John Conway"),
+        period: None,
+        speed: None,
+        description: "Heptominó simétrico que se estabiliza tras 173 generaciones.",
+        pattern: "3o$obo$obo!",
+    },
+    Preset {
+        name: GOSPER_GLIDER_GUN,
+        category: Category::Gun,
+        author: Some("Bill Gosper"),
+        period: Some(30),
+        speed: None,
+        description: "El primer patrón con crecimiento infinito: dispara un planeador cada 30 generaciones.",
+        pattern: "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    },
+    Preset {
+        name: "Simkin glider gun",
+        category: Category::Gun,
+        author: Some("Michael Simkin"),
+        period: Some(120),
+        speed: None,
+        description: "Cañón de planeadores de periodo 120 descubierto en 2015.",
+        pattern: "2o5b2o$2o5b2o2$4b2o$4b2o5$22b2ob2o$21bo5bo$21bo6bo2b2o$21b3o3bo3b2o$26bo4$20b2o$20bo$21b3o$23bo!",
+    },
+    Preset {
+        name: "Puffer train",
+        category: Category::Puffer,
+        author: Some("Bill Gosper"),
+        period: Some(140),
+        speed: Some("c/2"),
+        description: "Dos naves ligeras escoltan una reacción que deja una estela de escombros.",
+        pattern: "3bo$4bo$o3bo$b4o4$o$b2o$2bo$2bo$bo3$3bo$4bo$o3bo$b4o!",
+    },
+    Preset {
+        name: "Block-laying switch engine",
+        category: Category::Puffer,
+        author: Some("Paul Callahan"),
+        period: Some(288),
+        speed: Some("c/12"),
+        description: "Diez células que se convierten en un switch engine que deja una hilera de bloques.",
+        pattern: "6bo$4bob2o$4bobo$4bo$2bo$obo!",
+    },
+    Preset {
+        name: "5x5 infinite growth",
+        category: Category::Puffer,
+        author: Some("Paul Callahan"),
+        period: Some(288),
+        speed: Some("c/12"),
+        description: "El patrón de crecimiento infinito que cabe en una caja de 5x5.",
+        pattern: "3obo$o$3b2o$b2obo$obobo!",
+    },
+    Preset {
+        name: "One-cell-thick infinite growth",
+        category: Category::Puffer,
+        author: None,
+        period: Some(288),
+        speed: Some("c/12"),
+        description: "Una sola fila de células que crece sin límite.",
+        pattern: "8ob5o3b3o6b7ob5o!",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::Life;
+
+    fn run(life: &mut Life, generations: usize) {
+        for _ in 0..generations {
+            life.tick();
+        }
+    }
+
+    fn cells(life: &Life) -> HashSet<(isize, isize)> {
+        life.cells.iter().map(|cell| (cell.i, cell.j)).collect()
+    }
+
+    fn moved(cells: &HashSet<(isize, isize)>, (di, dj): (isize, isize)) -> HashSet<(isize, isize)> {
+        cells.iter().map(|(i, j)| (i + di, j + dj)).collect()
+    }
+
+    fn bounds(life: &Life) -> (isize, isize, isize, isize) {
+        let (top_left, bottom_right) = life.bounding_box().expect("the pattern does not die out");
+
+        (top_left.i, top_left.j, bottom_right.i, bottom_right.j)
+    }
+
+    // Drops the isolated groups of five cells, the escaping gliders, which
+    // would otherwise hide the front of the slower puffers.
+    fn without_gliders(life: &Life) -> Life {
+        let mut left = cells(life);
+        let mut kept = Life::default();
+
+        while let Some(&start) = left.iter().next() {
+            let mut group = vec![start];
+            let mut pending = vec![start];
+            left.remove(&start);
+
+            while let Some((i, j)) = pending.pop() {
+                for near in (i - 2..=i + 2).flat_map(|i| (j - 2..=j + 2).map(move |j| (i, j))) {
+                    if left.remove(&near) {
+                        group.push(near);
+                        pending.push(near);
+                    }
+                }
+            }
+
+            if group.len() != 5 {
+                for (i, j) in group {
+                    kept.populate(Cell { i, j });
+                }
+            }
+        }
+
+        kept
+    }
+
+    // Cells travelled per period, from a speed such as `c/4` or `2c/5`.
+    fn displacement(preset: &Preset) -> isize {
+        let period = preset.period.expect("the preset has a period") as isize;
+
+        match preset.speed {
+            Some(speed) => {
+                let index = speed.find("c/").expect("speeds are written as `c/n`");
+                let numerator = if index == 0 { 1 } else { speed[..index].parse().unwrap() };
+                let denominator: isize = speed[index + 2..].parse().unwrap();
+
+                assert_eq!(period * numerator % denominator, 0, "{}: {} over {}", preset.name, speed, period);
+
+                period * numerator / denominator
+            },
+            None => 0,
+        }
+    }
+
+    // The shift between two phases of a still life, oscillator or spaceship,
+    // if they have the same shape.
+    fn shift(from: &Life, to: &Life) -> Option<(isize, isize)> {
+        let (top, left, ..) = bounds(from);
+        let (i, j, ..) = bounds(to);
+        let offset = (i - top, j - left);
+
+        if from.cells.len() == to.cells.len() && moved(&cells(from), offset) == cells(to) {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn objects_have_their_period_and_speed() {
+        let objects = CATALOG.iter().filter(|preset| matches!(
+            preset.category,
+            Category::StillLife | Category::Oscillator | Category::Spaceship
+        ));
+
+        for preset in objects {
+            let period = preset.period.expect("objects have a period");
+            let start: Life = preset.cells().into_iter().collect();
+            let mut life = start.clone();
+
+            for generation in 1..period {
+                life.tick();
+
+                assert_eq!(shift(&start, &life), None, "{} repeats at {}", preset.name, generation);
+            }
+
+            life.tick();
+
+            let (di, dj) = shift(&start, &life)
+                .unwrap_or_else(|| panic!("{} does not repeat after {}", preset.name, period));
+
+            assert_eq!(di.abs().max(dj.abs()), displacement(preset), "{} moves by ({}, {})", preset.name, di, dj);
+        }
+    }
+
+    #[test]
+    fn guns_emit_a_glider_per_period() {
+        for preset in CATALOG.iter().filter(|preset| preset.category == Category::Gun) {
+            let period = preset.period.expect("guns have a period");
+            let mut life: Life = preset.cells().into_iter().collect();
+            let (top, left, bottom, right) = bounds(&life);
+            let gun = |life: &Life| -> HashSet<(isize, isize)> {
+                cells(life)
+                    .into_iter()
+                    .filter(|(i, j)| (top..=bottom).contains(i) && (left..=right).contains(j))
+                    .collect()
+            };
+
+            run(&mut life, 2 * period);
+
+            let (before, population) = (gun(&life), life.cells.len());
+
+            run(&mut life, period);
+
+            assert_eq!(gun(&life), before, "{} is not periodic", preset.name);
+            assert_eq!(life.cells.len(), population + 5, "{} does not add one glider", preset.name);
+        }
+    }
+
+    // Puffers are compared near their front, which repeats one period later
+    // moved by the displacement, while the debris stays behind.
+    #[test]
+    fn puffers_move_at_their_speed() {
+        const SETTLE: usize = 2000;
+        const DEPTH: isize = 40;
+
+        for preset in CATALOG.iter().filter(|preset| preset.category == Category::Puffer) {
+            let period = preset.period.expect("puffers have a period");
+            let distance = displacement(preset);
+            let mut life: Life = preset.cells().into_iter().collect();
+
+            run(&mut life, SETTLE);
+
+            let before = without_gliders(&life);
+
+            run(&mut life, period);
+
+            let after = without_gliders(&life);
+
+            let (top, left, bottom, right) = bounds(&before);
+            let (new_top, new_left, new_bottom, new_right) = bounds(&after);
+
+            let di = if new_bottom - bottom == distance { distance } else if top - new_top == distance { -distance } else { 0 };
+            let dj = if new_right - right == distance { distance } else if left - new_left == distance { -distance } else { 0 };
+
+            assert_ne!((di, dj), (0, 0), "{} has no front moving by {}", preset.name, distance);
+
+            let front = |cells: &HashSet<(isize, isize)>, (top, left, bottom, right)| -> HashSet<(isize, isize)> {
+                cells
+                    .iter()
+                    .filter(|(i, j)| {
+                        (di == 0 || (if di > 0 { bottom - i } else { i - top }) < DEPTH)
+                            && (dj == 0 || (if dj > 0 { right - j } else { j - left }) < DEPTH)
+                    })
+                    .copied()
+                    .collect()
+            };
+
+            assert_eq!(
+                moved(&front(&cells(&before), (top, left, bottom, right)), (di, dj)),
+                front(&cells(&after), (new_top, new_left, new_bottom, new_right)),
+                "{} does not repeat at its front",
+                preset.name,
+            );
+        }
+    }
+}
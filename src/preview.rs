@@ -0,0 +1,183 @@
+use iced::{button, slider, Align, Button, Element, Row, Slider, Text};
+
+use crate::selection::Selection;
+use crate::{Cell, Life, Message};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub is_enabled: bool,
+    pub generations: u16,
+}
+
+impl Settings {
+    pub const MAX_GENERATIONS: u16 = 50;
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            is_enabled: false,
+            generations: 10,
+        }
+    }
+}
+
+// The next generations of the cells in view. They are computed when drawn,
+// and kept until the universe, the view or the number of generations change.
+#[derive(Clone, Default)]
+pub struct Future {
+    // How many generations are shown, if any.
+    count: Option<usize>,
+    // The revision of the universe and the view predicted from.
+    key: Option<(usize, Selection)>,
+    generations: Vec<Vec<Cell>>,
+}
+
+impl Future {
+    // Returns whether what is shown changed.
+    pub fn show(&mut self, count: Option<usize>) -> bool {
+        if self.count == count {
+            return false;
+        }
+
+        self.count = count;
+        self.key = None;
+        self.generations.clear();
+        true
+    }
+
+    // `revision` tells apart the states of the universe.
+    pub fn generations(&mut self, life: &Life, revision: usize, visible: Selection) -> &[Vec<Cell>] {
+        let count = match self.count {
+            Some(count) => count,
+            None => return &[],
+        };
+
+        if self.key != Some((revision, visible)) {
+            self.key = Some((revision, visible));
+            self.generations = predict(life, &visible, count);
+        }
+
+        &self.generations
+    }
+}
+
+// The cells of `visible` in each of the next `count` generations. A cell
+// only affects cells one step further per generation, so running the cells
+// within `count` of the view gives its exact future.
+pub fn predict(life: &Life, visible: &Selection, count: usize) -> Vec<Vec<Cell>> {
+    let margin = count as isize;
+
+    let around = Selection::new(
+        Cell {
+            i: visible.top_left.i.saturating_sub(margin),
+            j: visible.top_left.j.saturating_sub(margin),
+        },
+        Cell {
+            i: visible.bottom_right.i.saturating_add(margin),
+            j: visible.bottom_right.j.saturating_add(margin),
+        },
+    );
+
+    let mut future: Life = life.within(&around).into_iter().collect();
+
+    (0..count)
+        .map(|_| {
+            future.tick();
+            future.cells.iter().filter(|cell| visible.contains(cell)).copied().collect()
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct Bar {
+    toggle: button::State,
+    generations: slider::State,
+}
+
+impl Bar {
+    pub fn view(&mut self, settings: Settings) -> Element<Message> {
+        Row::new()
+            .padding(10)
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(
+                Button::new(
+                    &mut self.toggle,
+                    Text::new(if settings.is_enabled { "Ocultar futuro" } else { "Mostrar futuro" }),
+                )
+                .on_press(Message::TogglePreview)
+            )
+            .push(Slider::new(
+                &mut self.generations,
+                1..=Settings::MAX_GENERATIONS,
+                settings.generations,
+                Message::PreviewGenerationsChanged,
+            ))
+            .push(Text::new(format!("{} gen a futuro (en pausa)", settings.generations)).size(16))
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    // A dense soup from a fixed linear congruential sequence.
+    fn soup(size: isize) -> Life {
+        let mut state: u64 = 7;
+
+        (0..size)
+            .flat_map(|i| (0..size).map(move |j| Cell { i, j }))
+            .filter(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                state >> 62 < 2
+            })
+            .collect()
+    }
+
+    #[test]
+    fn predicts_the_cells_in_view_as_life_ticks() {
+        let life = soup(40);
+        let visible = Selection::new(Cell { i: 10, j: 5 }, Cell { i: 25, j: 30 });
+        let predicted = predict(&life, &visible, 20);
+        let mut ticked = life.clone();
+
+        assert_eq!(predicted.len(), 20);
+
+        for (generation, cells) in predicted.iter().enumerate() {
+            ticked.tick();
+
+            let expected: HashSet<Cell> = ticked.cells.iter().filter(|cell| visible.contains(cell)).copied().collect();
+
+            assert_eq!(cells.iter().copied().collect::<HashSet<Cell>>(), expected, "generation {}", generation + 1);
+        }
+    }
+
+    #[test]
+    fn computes_only_when_shown_and_changed() {
+        let life = soup(20);
+        let visible = Selection::new(Cell { i: 0, j: 0 }, Cell { i: 19, j: 19 });
+        let mut future = Future::default();
+
+        assert!(future.generations(&life, 0, visible).is_empty());
+        assert!(future.show(Some(5)));
+        assert!(!future.show(Some(5)));
+        assert_eq!(future.generations(&life, 0, visible).len(), 5);
+        assert_eq!(future.key, Some((0, visible)));
+
+        let moved = Selection::new(Cell { i: 5, j: 5 }, Cell { i: 30, j: 30 });
+
+        let sets = |generations: &[Vec<Cell>]| -> Vec<HashSet<Cell>> {
+            generations.iter().map(|cells| cells.iter().copied().collect()).collect()
+        };
+
+        assert_eq!(sets(future.generations(&life, 0, moved)), sets(&predict(&life, &moved, 5)));
+        assert_eq!(future.key, Some((0, moved)));
+
+        assert!(future.show(None));
+        assert!(future.generations(&life, 1, moved).is_empty());
+    }
+}
@@ -0,0 +1,482 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path as FilePath;
+
+use iced::canvas::{Frame, Path, Stroke};
+use iced::{Color, Point, Size, Vector};
+
+// The drawing operations shared by the GPU `canvas::Frame` and the CPU
+// `Pixmap`, so a program can describe its drawing once and render it either
+// in the window or headless.
+pub trait Surface {
+    fn size(&self) -> Size;
+
+    fn center(&self) -> Point {
+        let size = self.size();
+
+        Point::new(size.width / 2.0, size.height / 2.0)
+    }
+
+    fn fill(&mut self, shape: &Shape, color: Color);
+
+    fn fill_rectangle(&mut self, top_left: Point, size: Size, color: Color);
+
+    fn stroke(&mut self, shape: &Shape, stroke: Stroke);
+
+    fn with_save<F: FnOnce(&mut Self)>(&mut self, f: F) where Self: Sized;
+
+    fn translate(&mut self, translation: Vector);
+
+    fn rotate(&mut self, angle: f32);
+
+    fn scale(&mut self, scale: f32);
+}
+
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Line { from: Point, to: Point },
+    Rectangle { top_left: Point, size: Size },
+    Rectangles(Vec<(Point, Size)>),
+    Circle { center: Point, radius: f32 },
+}
+
+impl Shape {
+    pub fn path(&self) -> Path {
+        match self {
+            Shape::Line { from, to } => Path::line(*from, *to),
+            Shape::Rectangle { top_left, size } => Path::rectangle(*top_left, *size),
+            Shape::Rectangles(rectangles) => Path::new(|path| {
+                for (top_left, size) in rectangles {
+                    path.rectangle(*top_left, *size);
+                }
+            }),
+            Shape::Circle { center, radius } => Path::circle(*center, *radius),
+        }
+    }
+
+    // Closed outlines for filling, or open polylines for stroking.
+    fn outlines(&self, scale: f32) -> Vec<Vec<Point>> {
+        let rectangle = |top_left: &Point, size: &Size| vec![
+            *top_left,
+            Point::new(top_left.x + size.width, top_left.y),
+            Point::new(top_left.x + size.width, top_left.y + size.height),
+            Point::new(top_left.x, top_left.y + size.height),
+            *top_left,
+        ];
+
+        match self {
+            Shape::Line { from, to } => vec![vec![*from, *to]],
+            Shape::Rectangle { top_left, size } => vec![rectangle(top_left, size)],
+            Shape::Rectangles(rectangles) => rectangles
+                .iter()
+                .map(|(top_left, size)| rectangle(top_left, size))
+                .collect(),
+            Shape::Circle { center, radius } => {
+                let segments = ((radius * scale).ceil() as usize).clamp(16, 256);
+
+                vec![(0..=segments)
+                    .map(|segment| {
+                        let angle = segment as f32 / segments as f32 * 2.0 * std::f32::consts::PI;
+
+                        Point::new(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+                    })
+                    .collect()]
+            },
+        }
+    }
+}
+
+impl Surface for Frame {
+    fn size(&self) -> Size {
+        Frame::size(self)
+    }
+
+    fn fill(&mut self, shape: &Shape, color: Color) {
+        Frame::fill(self, &shape.path(), color);
+    }
+
+    fn fill_rectangle(&mut self, top_left: Point, size: Size, color: Color) {
+        Frame::fill_rectangle(self, top_left, size, color);
+    }
+
+    fn stroke(&mut self, shape: &Shape, stroke: Stroke) {
+        Frame::stroke(self, &shape.path(), stroke);
+    }
+
+    fn with_save<F: FnOnce(&mut Self)>(&mut self, f: F) {
+        Frame::with_save(self, f);
+    }
+
+    fn translate(&mut self, translation: Vector) {
+        Frame::translate(self, translation);
+    }
+
+    fn rotate(&mut self, angle: f32) {
+        Frame::rotate(self, angle);
+    }
+
+    fn scale(&mut self, scale: f32) {
+        Frame::scale(self, scale);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Transform {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    e: f32,
+    f: f32,
+}
+
+impl Transform {
+    const IDENTITY: Transform = Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 };
+
+    fn apply(&self, point: Point) -> Point {
+        Point::new(
+            self.a * point.x + self.c * point.y + self.e,
+            self.b * point.x + self.d * point.y + self.f,
+        )
+    }
+
+    fn scale_factor(&self) -> f32 {
+        (self.a * self.d - self.b * self.c).abs().sqrt()
+    }
+}
+
+// A CPU render target holding straight RGBA8 pixels. Shapes are rasterized
+// without antialiasing by sampling pixel centers, which keeps the output
+// identical across machines.
+#[derive(Debug, Clone)]
+pub struct Pixmap {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+    transform: Transform,
+    stack: Vec<Transform>,
+}
+
+impl Pixmap {
+    pub fn new(width: u32, height: u32) -> Self {
+        Pixmap {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+            transform: Transform::IDENTITY,
+            stack: Vec::new(),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        let mut pixel = [0; 4];
+
+        pixel.copy_from_slice(&self.pixels[index..index + 4]);
+        pixel
+    }
+
+    pub fn save_png(&self, path: impl AsRef<FilePath>) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+
+        let mut encoder = png::Encoder::new(file, self.width, self.height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+
+        Ok(())
+    }
+
+    // Fills the union of the given polygons, in device coordinates, using the
+    // nonzero winding rule.
+    fn fill_polygons(&mut self, polygons: &[Vec<Point>], color: Color) {
+        let points = polygons.iter().flatten();
+
+        let top = points.clone().map(|point| point.y).fold(f32::INFINITY, f32::min);
+        let bottom = points.map(|point| point.y).fold(f32::NEG_INFINITY, f32::max);
+
+        if bottom <= top {
+            return;
+        }
+
+        let first_row = (top - 0.5).ceil().max(0.0) as u32;
+        let last_row = ((bottom - 0.5).floor() as i64).min(self.height as i64 - 1);
+
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+
+        for row in first_row as i64..=last_row {
+            let y = row as f32 + 0.5;
+
+            crossings.clear();
+
+            for polygon in polygons {
+                for edge in polygon.windows(2) {
+                    let (from, to) = (edge[0], edge[1]);
+
+                    if (from.y <= y) != (to.y <= y) {
+                        let x = from.x + (y - from.y) / (to.y - from.y) * (to.x - from.x);
+
+                        crossings.push((x, if to.y > from.y { 1 } else { -1 }));
+                    }
+                }
+            }
+
+            crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+            let mut winding = 0;
+
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+
+                if winding != 0 {
+                    let start = (pair[0].0 - 0.5).ceil().max(0.0) as u32;
+                    let end = ((pair[1].0 - 0.5).floor() as i64).min(self.width as i64 - 1);
+
+                    for column in start as i64..=end {
+                        self.blend(column as u32, row as u32, color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn blend(&mut self, x: u32, y: u32, color: Color) {
+        let index = (y as usize * self.width as usize + x as usize) * 4;
+        let pixel = &mut self.pixels[index..index + 4];
+
+        let alpha = color.a.clamp(0.0, 1.0);
+        let source = [color.r, color.g, color.b];
+        let destination_alpha = pixel[3] as f32 / 255.0;
+        let out_alpha = alpha + destination_alpha * (1.0 - alpha);
+
+        if out_alpha <= 0.0 {
+            return;
+        }
+
+        for channel in 0..3 {
+            let destination = pixel[channel] as f32 / 255.0;
+            let value = (source[channel].clamp(0.0, 1.0) * alpha
+                + destination * destination_alpha * (1.0 - alpha)) / out_alpha;
+
+            pixel[channel] = (value * 255.0).round() as u8;
+        }
+
+        pixel[3] = (out_alpha * 255.0).round() as u8;
+    }
+}
+
+impl Surface for Pixmap {
+    fn size(&self) -> Size {
+        Size::new(self.width as f32, self.height as f32)
+    }
+
+    fn fill(&mut self, shape: &Shape, color: Color) {
+        let transform = self.transform;
+
+        let polygons: Vec<Vec<Point>> = shape
+            .outlines(transform.scale_factor())
+            .into_iter()
+            .map(|outline| outline.into_iter().map(|point| transform.apply(point)).collect())
+            .collect();
+
+        self.fill_polygons(&polygons, color);
+    }
+
+    // Like the GPU frame, the rectangle stays axis aligned under rotation.
+    fn fill_rectangle(&mut self, top_left: Point, size: Size, color: Color) {
+        let top_left = self.transform.apply(top_left);
+        let extent = Vector::new(
+            self.transform.a * size.width + self.transform.c * size.height,
+            self.transform.b * size.width + self.transform.d * size.height,
+        );
+
+        let rectangle = Shape::Rectangle {
+            top_left,
+            size: Size::new(extent.x, extent.y),
+        };
+
+        self.fill_polygons(&rectangle.outlines(1.0), color);
+    }
+
+    // Stroke widths are in pixels, as in the GPU frame, which strokes the
+    // path after transforming it.
+    fn stroke(&mut self, shape: &Shape, stroke: Stroke) {
+        let transform = self.transform;
+        let half = stroke.width / 2.0;
+
+        let mut quads = Vec::new();
+
+        for outline in shape.outlines(transform.scale_factor()) {
+            let outline: Vec<Point> = outline.into_iter().map(|point| transform.apply(point)).collect();
+
+            for segment in outline.windows(2) {
+                let (from, to) = (segment[0], segment[1]);
+                let length = from.distance(to);
+
+                if length == 0.0 {
+                    continue;
+                }
+
+                let normal = Vector::new(
+                    -(to.y - from.y) / length * half,
+                    (to.x - from.x) / length * half,
+                );
+
+                let quad = vec![from + normal, to + normal, to - normal, from - normal, from + normal];
+
+                let area: f32 = quad.windows(2).map(|edge| edge[0].x * edge[1].y - edge[1].x * edge[0].y).sum();
+
+                quads.push(if area < 0.0 { quad.into_iter().rev().collect() } else { quad });
+            }
+        }
+
+        self.fill_polygons(&quads, stroke.color);
+    }
+
+    fn with_save<F: FnOnce(&mut Self)>(&mut self, f: F) {
+        self.stack.push(self.transform);
+
+        f(self);
+
+        self.transform = self.stack.pop().unwrap_or(Transform::IDENTITY);
+    }
+
+    fn translate(&mut self, translation: Vector) {
+        let t = &mut self.transform;
+
+        t.e += t.a * translation.x + t.c * translation.y;
+        t.f += t.b * translation.x + t.d * translation.y;
+    }
+
+    fn rotate(&mut self, angle: f32) {
+        let t = self.transform;
+        let (sin, cos) = angle.sin_cos();
+
+        self.transform = Transform {
+            a: t.a * cos + t.c * sin,
+            b: t.b * cos + t.d * sin,
+            c: t.c * cos - t.a * sin,
+            d: t.d * cos - t.b * sin,
+            ..t
+        };
+    }
+
+    fn scale(&mut self, scale: f32) {
+        let t = &mut self.transform;
+
+        t.a *= scale;
+        t.b *= scale;
+        t.c *= scale;
+        t.d *= scale;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color { r: 1.0, g: 0.0, b: 0.0, a: 1.0 };
+
+    // One character per pixel: `#` opaque, `+` translucent, `.` empty.
+    fn render(pixmap: &Pixmap) -> String {
+        (0..pixmap.height())
+            .map(|y| {
+                (0..pixmap.width())
+                    .map(|x| match pixmap.pixel(x, y)[3] {
+                        255 => '#',
+                        0 => '.',
+                        _ => '+',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn fills_rectangles_on_pixel_boundaries() {
+        let mut pixmap = Pixmap::new(6, 4);
+
+        pixmap.fill_rectangle(Point::new(1.0, 1.0), Size::new(3.0, 2.0), RED);
+
+        assert_eq!(render(&pixmap), "......\n.###..\n.###..\n......");
+        assert_eq!(pixmap.pixel(1, 1), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn applies_transforms() {
+        let mut pixmap = Pixmap::new(8, 8);
+
+        pixmap.with_save(|pixmap| {
+            pixmap.translate(Vector::new(2.0, 2.0));
+            pixmap.scale(2.0);
+            pixmap.fill(&Shape::Rectangles(vec![(Point::ORIGIN, Size::UNIT), (Point::new(1.0, 1.0), Size::UNIT)]), RED);
+        });
+
+        pixmap.fill_rectangle(Point::new(7.0, 7.0), Size::UNIT, RED);
+
+        assert_eq!(
+            render(&pixmap),
+            "........\n........\n..##....\n..##....\n....##..\n....##..\n........\n.......#",
+        );
+    }
+
+    #[test]
+    fn fills_circles() {
+        let mut pixmap = Pixmap::new(21, 21);
+
+        pixmap.fill(&Shape::Circle { center: Point::new(10.5, 10.5), radius: 8.0 }, RED);
+
+        let filled = pixmap.pixels().chunks(4).filter(|pixel| pixel[3] == 255).count() as f32;
+        let area = std::f32::consts::PI * 64.0;
+
+        assert!((filled - area).abs() < area * 0.05, "{} pixels for an area of {}", filled, area);
+        assert_eq!(pixmap.pixel(10, 10)[3], 255);
+        assert_eq!(pixmap.pixel(0, 0)[3], 0);
+        assert_eq!(pixmap.pixel(10, 1)[3], 0);
+    }
+
+    #[test]
+    fn strokes_in_pixels() {
+        let mut pixmap = Pixmap::new(6, 5);
+
+        pixmap.scale(10.0);
+        pixmap.stroke(
+            &Shape::Line { from: Point::new(0.1, 0.25), to: Point::new(0.5, 0.25) },
+            Stroke { color: RED, width: 2.0, ..Stroke::default() },
+        );
+
+        assert_eq!(render(&pixmap), "......\n.####.\n.####.\n......\n......");
+    }
+
+    #[test]
+    fn blends_translucent_colors() {
+        let mut pixmap = Pixmap::new(2, 1);
+
+        pixmap.fill_rectangle(Point::ORIGIN, Size::new(2.0, 1.0), Color::BLACK);
+        pixmap.fill_rectangle(Point::ORIGIN, Size::UNIT, Color { a: 0.5, ..Color::WHITE });
+
+        assert_eq!(pixmap.pixel(0, 0), [128, 128, 128, 255]);
+        assert_eq!(pixmap.pixel(1, 0), [0, 0, 0, 255]);
+
+        let mut empty = Pixmap::new(1, 1);
+        empty.fill_rectangle(Point::ORIGIN, Size::UNIT, Color { a: 0.5, ..RED });
+
+        assert_eq!(render(&empty), "+");
+        assert_eq!(empty.pixel(0, 0), [255, 0, 0, 128]);
+    }
+}
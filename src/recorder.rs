@@ -0,0 +1,270 @@
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+use iced::Color;
+
+use crate::{rgb8, Cell, Life};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Crop {
+    Fixed { origin: Cell, rows: usize, columns: usize },
+    Fit { margin: usize },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    pub background: Color,
+    pub cell: Color,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            background: Color::BLACK,
+            cell: Color::WHITE,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Gif(gif::EncodingError),
+    TooLarge { width: usize, height: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{}", error),
+            Error::Gif(error) => write!(f, "{}", error),
+            Error::TooLarge { width, height } => {
+                write!(f, "a {}x{} frame does not fit in a GIF", width, height)
+            },
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<gif::EncodingError> for Error {
+    fn from(error: gif::EncodingError) -> Self {
+        Error::Gif(error)
+    }
+}
+
+// Renders a range of generations into an animated GIF without opening a
+// window: every frame is drawn straight into an indexed pixel buffer and
+// encoded as soon as its generation is simulated.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    pub generations: Range<usize>,
+    pub delay: u16,
+    pub crop: Crop,
+    pub scale: usize,
+    pub palette: Palette,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Recorder {
+            generations: 0..100,
+            delay: 5,
+            crop: Crop::Fit { margin: 2 },
+            scale: Cell::SIZE,
+            palette: Palette::default(),
+        }
+    }
+}
+
+impl Recorder {
+    pub fn record(&self, life: &Life, path: impl AsRef<Path>) -> Result<(), Error> {
+        let (life, window) = self.prepare(life)?;
+
+        self.encode(life, window, File::create(path)?)
+    }
+
+    // Runs up to the first generation to record and finds the window of
+    // cells the frames show, checking it fits in a GIF.
+    fn prepare(&self, life: &Life) -> Result<(Life, Window), Error> {
+        let mut life = life.clone();
+
+        for _ in 0..self.generations.start {
+            life.tick();
+        }
+
+        let window = match self.crop {
+            Crop::Fixed { origin, rows, columns } => Window { origin, rows, columns },
+            Crop::Fit { margin } => Self::fit(&life, self.generations.len(), margin),
+        };
+
+        let width = window.columns * self.scale;
+        let height = window.rows * self.scale;
+
+        if width == 0 || height == 0 || width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(Error::TooLarge { width, height });
+        }
+
+        Ok((life, window))
+    }
+
+    fn encode(&self, mut life: Life, window: Window, output: impl io::Write) -> Result<(), Error> {
+        let Window { origin, rows, columns } = window;
+        let width = columns * self.scale;
+        let height = rows * self.scale;
+
+        let palette = [rgb8(self.palette.background), rgb8(self.palette.cell)].concat();
+
+        let mut encoder = gif::Encoder::new(output, width as u16, height as u16, &palette)?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+
+        let mut pixels = vec![0u8; width * height];
+
+        for _ in self.generations.clone() {
+            for pixel in pixels.iter_mut() {
+                *pixel = 0;
+            }
+
+            for cell in &life.cells {
+                let i = cell.i - origin.i;
+                let j = cell.j - origin.j;
+
+                if i < 0 || j < 0 || i as usize >= rows || j as usize >= columns {
+                    continue;
+                }
+
+                for y in i as usize * self.scale..(i as usize + 1) * self.scale {
+                    let row = y * width;
+
+                    for pixel in &mut pixels[row + j as usize * self.scale..row + (j as usize + 1) * self.scale] {
+                        *pixel = 1;
+                    }
+                }
+            }
+
+            let mut frame = gif::Frame::from_indexed_pixels(width as u16, height as u16, &pixels, None);
+            frame.delay = self.delay;
+
+            encoder.write_frame(&frame)?;
+
+            life.tick();
+        }
+
+        Ok(())
+    }
+
+    // The bounding box of the next `generations` generations, found by
+    // running them ahead once since the frames are not kept.
+    fn fit(life: &Life, generations: usize, margin: usize) -> Window {
+        let mut life = life.clone();
+        let mut bounds: Option<(Cell, Cell)> = None;
+
+        for _ in 0..generations {
+            if let Some((top_left, bottom_right)) = life.bounding_box() {
+                bounds = Some(match bounds {
+                    Some((top, bottom)) => (
+                        Cell { i: top.i.min(top_left.i), j: top.j.min(top_left.j) },
+                        Cell { i: bottom.i.max(bottom_right.i), j: bottom.j.max(bottom_right.j) },
+                    ),
+                    None => (top_left, bottom_right),
+                });
+            }
+
+            life.tick();
+        }
+
+        match bounds {
+            Some((top_left, bottom_right)) => {
+                let margin = margin as isize;
+
+                Window {
+                    origin: Cell { i: top_left.i - margin, j: top_left.j - margin },
+                    rows: (bottom_right.i - top_left.i + 1 + 2 * margin) as usize,
+                    columns: (bottom_right.j - top_left.j + 1 + 2 * margin) as usize,
+                }
+            },
+            None => Window { origin: Cell { i: 0, j: 0 }, rows: 1, columns: 1 },
+        }
+    }
+}
+
+// The cells shown in every frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Window {
+    origin: Cell,
+    rows: usize,
+    columns: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glider() -> Life {
+        [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)].iter().map(|&(i, j)| Cell { i, j }).collect()
+    }
+
+    // The number of frames and the size of a recorded GIF.
+    fn decode(gif: &[u8]) -> (usize, u16, u16) {
+        let mut decoder = gif::DecodeOptions::new().read_info(gif).unwrap();
+        let mut frames = 0;
+
+        while decoder.read_next_frame().unwrap().is_some() {
+            frames += 1;
+        }
+
+        (frames, decoder.width(), decoder.height())
+    }
+
+    #[test]
+    fn records_one_frame_per_generation() {
+        let recorder = Recorder {
+            generations: 4..12,
+            crop: Crop::Fit { margin: 1 },
+            scale: 3,
+            ..Recorder::default()
+        };
+
+        let (life, window) = recorder.prepare(&glider()).unwrap();
+        let mut gif = Vec::new();
+
+        recorder.encode(life, window, &mut gif).unwrap();
+
+        // The glider moves two cells in eight generations, from one cell on.
+        assert_eq!(window, Window { origin: Cell { i: 0, j: 0 }, rows: 7, columns: 7 });
+        assert_eq!(decode(&gif), (8, 21, 21));
+    }
+
+    #[test]
+    fn fits_every_recorded_generation() {
+        let mut life = glider();
+        let window = Recorder::fit(&life, 40, 0);
+
+        // Ten steps of one cell, and the glider's own four cells at most.
+        assert_eq!(window, Window { origin: Cell { i: 0, j: 0 }, rows: 13, columns: 13 });
+
+        for _ in 0..40 {
+            assert!(life.cells.iter().all(|cell| cell.i < 13 && cell.j < 13));
+            life.tick();
+        }
+
+        assert_eq!(Recorder::fit(&Life::default(), 10, 2), Window { origin: Cell { i: 0, j: 0 }, rows: 1, columns: 1 });
+    }
+
+    #[test]
+    fn rejects_frames_that_do_not_fit() {
+        let recorder = Recorder {
+            crop: Crop::Fixed { origin: Cell { i: 0, j: 0 }, rows: 10_000, columns: 10 },
+            ..Recorder::default()
+        };
+
+        assert!(matches!(recorder.prepare(&glider()), Err(Error::TooLarge { width: 100, height: 100_000 })));
+    }
+}
@@ -0,0 +1,254 @@
+use iced::{button, Button, Element, Row, Text};
+use rand::Rng;
+
+use crate::{Cell, Life, Message};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Cut,
+    Delete,
+    Rotate,
+    FlipHorizontal,
+    FlipVertical,
+    Randomize,
+    ClearOutside,
+    Deselect,
+}
+
+// An inclusive rectangle of cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    pub top_left: Cell,
+    pub bottom_right: Cell,
+}
+
+impl Selection {
+    const RANDOM_DENSITY: f64 = 0.5;
+    const RANDOM_LIMIT: isize = 1_000_000;
+
+    pub fn new(from: Cell, to: Cell) -> Self {
+        Selection {
+            top_left: Cell { i: from.i.min(to.i), j: from.j.min(to.j) },
+            bottom_right: Cell { i: from.i.max(to.i), j: from.j.max(to.j) },
+        }
+    }
+
+    pub fn rows(&self) -> isize {
+        self.bottom_right.i - self.top_left.i + 1
+    }
+
+    pub fn columns(&self) -> isize {
+        self.bottom_right.j - self.top_left.j + 1
+    }
+
+    pub fn contains(&self, cell: &Cell) -> bool {
+        (self.top_left.i..=self.bottom_right.i).contains(&cell.i)
+            && (self.top_left.j..=self.bottom_right.j).contains(&cell.j)
+    }
+
+    // The live cells inside, relative to the top left corner.
+    pub fn cells(&self, life: &Life) -> Vec<Cell> {
+        life.cells
+            .iter()
+            .filter(|cell| self.contains(cell))
+            .map(|cell| Cell {
+                i: cell.i - self.top_left.i,
+                j: cell.j - self.top_left.j,
+            })
+            .collect()
+    }
+
+    pub fn clear(&self, life: &mut Life) {
+        life.cells.retain(|cell| !self.contains(cell));
+    }
+
+    pub fn clear_outside(&self, life: &mut Life) {
+        life.cells.retain(|cell| self.contains(cell));
+    }
+
+    // Rotates the contents a quarter turn clockwise around the top left
+    // corner. The selection becomes the rotated rectangle.
+    pub fn rotate(&self, life: &mut Life) -> Selection {
+        let rows = self.rows();
+        let cells = self.cells(life);
+
+        let rotated = Selection {
+            top_left: self.top_left,
+            bottom_right: Cell {
+                i: self.top_left.i + self.columns() - 1,
+                j: self.top_left.j + rows - 1,
+            },
+        };
+
+        self.clear(life);
+        rotated.clear(life);
+
+        for cell in cells {
+            life.populate(Cell {
+                i: self.top_left.i + cell.j,
+                j: self.top_left.j + rows - 1 - cell.i,
+            });
+        }
+
+        rotated
+    }
+
+    pub fn flip_horizontal(&self, life: &mut Life) {
+        let columns = self.columns();
+
+        self.replace(life, |cell| Cell { i: cell.i, j: columns - 1 - cell.j });
+    }
+
+    pub fn flip_vertical(&self, life: &mut Life) {
+        let rows = self.rows();
+
+        self.replace(life, |cell| Cell { i: rows - 1 - cell.i, j: cell.j });
+    }
+
+    // Every cell is drawn, so selections larger than `RANDOM_LIMIT` cells,
+    // which a single drag covers when zoomed far out, are left as they are.
+    pub fn randomize(&self, life: &mut Life) {
+        if self.rows().saturating_mul(self.columns()) > Self::RANDOM_LIMIT {
+            return;
+        }
+
+        let mut rng = rand::thread_rng();
+
+        self.clear(life);
+
+        for i in self.top_left.i..=self.bottom_right.i {
+            for j in self.top_left.j..=self.bottom_right.j {
+                if rng.gen_bool(Self::RANDOM_DENSITY) {
+                    life.populate(Cell { i, j });
+                }
+            }
+        }
+    }
+
+    fn replace(&self, life: &mut Life, f: impl Fn(Cell) -> Cell) {
+        let cells = self.cells(life);
+
+        self.clear(life);
+
+        for cell in cells {
+            let cell = f(cell);
+
+            life.populate(Cell {
+                i: self.top_left.i + cell.i,
+                j: self.top_left.j + cell.j,
+            });
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Bar {
+    cut: button::State,
+    delete: button::State,
+    rotate: button::State,
+    flip_horizontal: button::State,
+    flip_vertical: button::State,
+    randomize: button::State,
+    clear_outside: button::State,
+    deselect: button::State,
+}
+
+impl Bar {
+    pub fn view(&mut self, selection: Selection) -> Element<Message> {
+        Row::new()
+            .padding(10)
+            .spacing(10)
+            .push(Text::new(format!("{}x{}", selection.columns(), selection.rows())))
+            .push(edit_button(&mut self.cut, "Cortar", Edit::Cut))
+            .push(edit_button(&mut self.delete, "Borrar", Edit::Delete))
+            .push(edit_button(&mut self.clear_outside, "Borrar fuera", Edit::ClearOutside))
+            .push(edit_button(&mut self.rotate, "Girar 90°", Edit::Rotate))
+            .push(edit_button(&mut self.flip_horizontal, "Voltear ↔", Edit::FlipHorizontal))
+            .push(edit_button(&mut self.flip_vertical, "Voltear ↕", Edit::FlipVertical))
+            .push(edit_button(&mut self.randomize, "Aleatorio", Edit::Randomize))
+            .push(edit_button(&mut self.deselect, "Deseleccionar", Edit::Deselect))
+            .into()
+    }
+}
+
+fn edit_button<'a>(state: &'a mut button::State, label: &str, edit: Edit) -> Button<'a, Message> {
+    Button::new(state, Text::new(label).size(16)).on_press(Message::Edit(edit))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    // Two rows by three columns at (5, 5), plus cells outside it:
+    //
+    //     X X .
+    //     . . X
+    fn sample() -> Life {
+        cells(&[(5, 5), (5, 6), (6, 7), (0, 0), (7, 6)])
+    }
+
+    fn cells(cells: &[(isize, isize)]) -> Life {
+        cells.iter().map(|&(i, j)| Cell { i, j }).collect()
+    }
+
+    fn selection() -> Selection {
+        Selection::new(Cell { i: 6, j: 7 }, Cell { i: 5, j: 5 })
+    }
+
+    #[test]
+    fn rotates_clockwise() {
+        let mut life = sample();
+        let rotated = selection().rotate(&mut life);
+
+        assert_eq!(rotated, Selection::new(Cell { i: 5, j: 5 }, Cell { i: 7, j: 6 }));
+        assert_eq!(life.cells, cells(&[(5, 6), (6, 6), (7, 5), (0, 0)]).cells);
+    }
+
+    #[test]
+    fn flips() {
+        let mut life = sample();
+        selection().flip_horizontal(&mut life);
+
+        assert_eq!(life.cells, cells(&[(5, 7), (5, 6), (6, 5), (0, 0), (7, 6)]).cells);
+
+        let mut life = sample();
+        selection().flip_vertical(&mut life);
+
+        assert_eq!(life.cells, cells(&[(6, 5), (6, 6), (5, 7), (0, 0), (7, 6)]).cells);
+    }
+
+    #[test]
+    fn cuts_and_clears() {
+        let mut life = sample();
+        let copied: HashSet<Cell> = selection().cells(&life).into_iter().collect();
+
+        assert_eq!(copied, cells(&[(0, 0), (0, 1), (1, 2)]).cells);
+
+        selection().clear(&mut life);
+
+        assert_eq!(life.cells, cells(&[(0, 0), (7, 6)]).cells);
+
+        let mut life = sample();
+        selection().clear_outside(&mut life);
+
+        assert_eq!(life.cells, cells(&[(5, 5), (5, 6), (6, 7)]).cells);
+    }
+
+    #[test]
+    fn randomizes_only_inside_small_selections() {
+        let mut life = sample();
+        selection().randomize(&mut life);
+
+        assert!(life.contains(&Cell { i: 0, j: 0 }) && life.contains(&Cell { i: 7, j: 6 }));
+        assert_eq!(life.cells.iter().filter(|cell| !selection().contains(cell)).count(), 2);
+
+        let mut life = sample();
+        let huge = Selection::new(Cell { i: -(1 << 20), j: -(1 << 20) }, Cell { i: 1 << 20, j: 1 << 20 });
+
+        huge.randomize(&mut life);
+
+        assert_eq!(life.cells, sample().cells);
+    }
+}
@@ -0,0 +1,146 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use iced::Vector;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub name: String,
+    pub translation: Vector,
+    pub scaling: f32,
+}
+
+// State kept between runs, one `kind<TAB>fields...` line per entry.
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    pub bookmarks: Vec<Bookmark>,
+}
+
+// The file comes from `LIFE_SESSION`, falling back to `session.txt` in the
+// working directory.
+pub fn path() -> PathBuf {
+    std::env::var_os("LIFE_SESSION")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("session.txt"))
+}
+
+impl Session {
+    // A missing file is an empty session; unreadable lines are reported and
+    // skipped.
+    pub fn load(path: impl AsRef<Path>) -> (Session, Vec<String>) {
+        let mut session = Session::default();
+        let mut errors = Vec::new();
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return (session, errors),
+            Err(error) => {
+                errors.push(format!("{}: {}", path.as_ref().display(), error));
+
+                return (session, errors);
+            },
+        };
+
+        for (number, line) in text.lines().enumerate() {
+            let fields: Vec<&str> = line.split('\t').collect();
+
+            match fields.as_slice() {
+                [] | [""] => {},
+                ["bookmark", x, y, scaling, name] => match (x.parse(), y.parse(), scaling.parse()) {
+                    (Ok(x), Ok(y), Ok(scaling)) => session.bookmarks.push(Bookmark {
+                        name: String::from(*name),
+                        translation: Vector::new(x, y),
+                        scaling,
+                    }),
+                    _ => errors.push(format!("{}:{}: invalid bookmark", path.as_ref().display(), number + 1)),
+                },
+                _ => errors.push(format!("{}:{}: unknown entry", path.as_ref().display(), number + 1)),
+            }
+        }
+
+        (session, errors)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut text = String::new();
+
+        for bookmark in &self.bookmarks {
+            text.push_str(&format!(
+                "bookmark\t{}\t{}\t{}\t{}\n",
+                bookmark.translation.x,
+                bookmark.translation.y,
+                bookmark.scaling,
+                bookmark.name.replace(['\t', '\n'], " "),
+            ));
+        }
+
+        fs::write(path, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_bookmarks() {
+        let path = std::env::temp_dir().join(format!("life-session-{}.txt", std::process::id()));
+
+        let session = Session {
+            bookmarks: vec![
+                Bookmark { name: String::from("Origen"), translation: Vector::new(0.0, 0.0), scaling: 1.0 },
+                Bookmark { name: String::from("Lejos"), translation: Vector::new(-1234.5, 987.25), scaling: 0.003 },
+            ],
+        };
+
+        session.save(&path).unwrap();
+
+        let (loaded, errors) = Session::load(&path);
+
+        fs::remove_file(&path).unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(loaded.bookmarks, session.bookmarks);
+    }
+
+    #[test]
+    fn keeps_names_on_one_line() {
+        let path = std::env::temp_dir().join(format!("life-session-names-{}.txt", std::process::id()));
+
+        let session = Session {
+            bookmarks: vec![Bookmark { name: String::from("a\tb\nc"), translation: Vector::new(1.0, 2.0), scaling: 0.5 }],
+        };
+
+        session.save(&path).unwrap();
+
+        let (loaded, errors) = Session::load(&path);
+
+        fs::remove_file(&path).unwrap();
+
+        assert!(errors.is_empty());
+        assert_eq!(loaded.bookmarks[0].name, "a b c");
+    }
+
+    #[test]
+    fn skips_bad_lines() {
+        let path = std::env::temp_dir().join(format!("life-session-bad-{}.txt", std::process::id()));
+
+        fs::write(&path, "bookmark\t1\t2\t0.5\tUno\n\nbookmark\tx\t2\t1\tDos\ncolor\tred\n").unwrap();
+
+        let (loaded, errors) = Session::load(&path);
+        let display = path.display().to_string();
+
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.bookmarks.len(), 1);
+        assert_eq!(errors, vec![
+            format!("{}:3: invalid bookmark", display),
+            format!("{}:4: unknown entry", display),
+        ]);
+
+        let (missing, errors) = Session::load(std::env::temp_dir().join("life-session-missing.txt"));
+
+        assert!(missing.bookmarks.is_empty() && errors.is_empty());
+    }
+}
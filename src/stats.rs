@@ -0,0 +1,420 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use iced::canvas::{self, Cache, Canvas, Cursor, Geometry, Path, Stroke};
+use iced::{
+    button, slider, Align, Button, Color, Column, Element, HorizontalAlignment, Length, Point,
+    Rectangle, Row, Slider, Text, VerticalAlignment,
+};
+
+use crate::theme::Theme;
+use crate::{Life, Message};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub run: usize,
+    pub generation: usize,
+    pub population: usize,
+    pub births: usize,
+    pub deaths: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Sample {
+    pub fn new(life: &Life, generation: usize, births: usize, deaths: usize) -> Self {
+        let (width, height) = match life.bounding_box() {
+            Some((top_left, bottom_right)) => (
+                (bottom_right.j - top_left.j + 1) as usize,
+                (bottom_right.i - top_left.i + 1) as usize,
+            ),
+            None => (0, 0),
+        };
+
+        Sample {
+            run: 0,
+            generation,
+            population: life.cells.len(),
+            births,
+            deaths,
+            width,
+            height,
+        }
+    }
+}
+
+// One sample per generation, oldest first. Going back in time, by undoing or
+// resetting, starts a new run and keeps the samples of the abandoned one, so
+// the export has every generation simulated. Past `MAX_SAMPLES` the oldest
+// samples are dropped.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    samples: VecDeque<Sample>,
+    run: usize,
+    // The revision of the universe last observed.
+    revision: Option<usize>,
+}
+
+impl Stats {
+    pub const MAX_SAMPLES: usize = 100_000;
+
+    pub fn record(&mut self, sample: Sample) {
+        if matches!(self.samples.back(), Some(last) if last.generation >= sample.generation) {
+            self.run += 1;
+        }
+
+        self.samples.push_back(Sample { run: self.run, ..sample });
+
+        if self.samples.len() > Self::MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    // Keeps the last sample in step with edits made without advancing.
+    pub fn observe(&mut self, life: &Life, generation: usize, revision: usize) {
+        if self.revision == Some(revision) {
+            return;
+        }
+
+        self.revision = Some(revision);
+
+        match self.samples.back_mut() {
+            Some(last) if last.generation == generation => {
+                *last = Sample { run: last.run, ..Sample::new(life, generation, last.births, last.deaths) };
+            },
+            _ => self.record(Sample::new(life, generation, 0, 0)),
+        }
+    }
+
+    pub fn run(&self) -> usize {
+        self.run
+    }
+
+    // The last `count` samples of the current timeline: the current run,
+    // preceded by the generations of earlier runs that it branched from.
+    pub fn timeline(&self, count: usize) -> Vec<Sample> {
+        let mut timeline = Vec::new();
+        let mut run = self.run;
+        let mut first = usize::MAX;
+        let mut limit = usize::MAX;
+
+        for sample in self.samples.iter().rev() {
+            if timeline.len() == count {
+                break;
+            }
+
+            if sample.run != run {
+                run = sample.run;
+                limit = limit.min(first);
+            }
+
+            first = sample.generation;
+
+            if sample.generation < limit {
+                timeline.push(*sample);
+            }
+        }
+
+        timeline.reverse();
+        timeline
+    }
+
+    // The samples of the current run from `generation` on.
+    pub fn since(&self, generation: usize) -> Vec<Sample> {
+        let mut samples: Vec<Sample> = self
+            .samples
+            .iter()
+            .rev()
+            .take_while(|sample| sample.run == self.run && sample.generation >= generation)
+            .copied()
+            .collect();
+
+        samples.reverse();
+        samples
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("run,generation,population,births,deaths,width,height\n");
+
+        for sample in &self.samples {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                sample.run,
+                sample.generation,
+                sample.population,
+                sample.births,
+                sample.deaths,
+                sample.width,
+                sample.height,
+            );
+        }
+
+        csv
+    }
+}
+
+// The last generations charted with one scale per series, so small counts
+// like births stay readable next to the population.
+struct Chart {
+    cache: Cache,
+    samples: VecDeque<Sample>,
+    background: Color,
+    text: Color,
+    colors: [Color; 4],
+}
+
+impl Chart {
+    const NAMES: [&'static str; 4] = ["Población", "Nacimientos", "Muertes", "Caja"];
+
+    fn values(sample: &Sample) -> [usize; 4] {
+        [sample.population, sample.births, sample.deaths, sample.width * sample.height]
+    }
+}
+
+impl canvas::Program<Message> for Chart {
+    fn draw(&self, bounds: Rectangle, _cursor: Cursor) -> Vec<Geometry> {
+        let chart = self.cache.draw(bounds.size(), |frame| {
+            frame.fill_rectangle(Point::ORIGIN, frame.size(), self.background);
+
+            let (first, last) = match (self.samples.front(), self.samples.back()) {
+                (Some(first), Some(last)) => (first, last),
+                _ => return,
+            };
+
+            let top = 24.0;
+            let height = frame.height() - top - 4.0;
+            let step = frame.width() / (self.samples.len().max(2) - 1) as f32;
+
+            for (series, color) in self.colors.iter().enumerate() {
+                let max = self.samples.iter().map(|sample| Self::values(sample)[series]).max().unwrap_or(0).max(1);
+
+                let line = Path::new(|path| {
+                    for (index, sample) in self.samples.iter().enumerate() {
+                        let point = Point::new(
+                            index as f32 * step,
+                            top + height * (1.0 - Self::values(sample)[series] as f32 / max as f32),
+                        );
+
+                        if index == 0 {
+                            path.move_to(point);
+                        } else {
+                            path.line_to(point);
+                        }
+                    }
+                });
+
+                frame.stroke(&line, Stroke {
+                    color: *color,
+                    width: 1.5,
+                    ..Stroke::default()
+                });
+
+                let value = if series == 3 {
+                    format!("{}×{}", last.width, last.height)
+                } else {
+                    Self::values(last)[series].to_string()
+                };
+
+                frame.fill_text(canvas::Text {
+                    content: format!("{}: {}", Self::NAMES[series], value),
+                    position: Point::new(8.0 + series as f32 * 160.0, top / 2.0),
+                    color: *color,
+                    size: 14.0,
+                    vertical_alignment: VerticalAlignment::Center,
+                    ..canvas::Text::default()
+                });
+            }
+
+            frame.fill_text(canvas::Text {
+                content: format!("Generaciones {}–{}", first.generation, last.generation),
+                position: Point::new(frame.width() - 8.0, top / 2.0),
+                color: self.text,
+                size: 14.0,
+                horizontal_alignment: HorizontalAlignment::Right,
+                vertical_alignment: VerticalAlignment::Center,
+                ..canvas::Text::default()
+            });
+        });
+
+        vec![chart]
+    }
+}
+
+pub struct Panel {
+    chart: Chart,
+    window: u16,
+    slider: slider::State,
+    export: button::State,
+}
+
+impl Panel {
+    pub const MIN_WINDOW: u16 = 10;
+    pub const MAX_WINDOW: u16 = 5000;
+    const HEIGHT: u16 = 120;
+
+    pub fn new(theme: &Theme) -> Self {
+        let mut panel = Panel {
+            chart: Chart {
+                cache: Cache::default(),
+                samples: VecDeque::new(),
+                background: Color::BLACK,
+                text: Color::WHITE,
+                colors: [Color::WHITE; 4],
+            },
+            window: 200,
+            slider: slider::State::default(),
+            export: button::State::default(),
+        };
+
+        panel.set_theme(theme);
+        panel
+    }
+
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.chart.background = theme.background;
+        self.chart.text = theme.text;
+        self.chart.colors = [
+            Color { a: 1.0, ..theme.cell },
+            Color { a: 1.0, ..theme.fate[2] },
+            Color { a: 1.0, ..theme.fate[1] },
+            Color { a: 1.0, ..theme.follow },
+        ];
+        self.chart.cache.clear();
+    }
+
+    pub fn set_window(&mut self, window: u16) {
+        self.window = window;
+        self.chart.samples.clear();
+    }
+
+    // Charts the last `window` samples of the timeline. While the run goes
+    // on, only the samples since the last one charted are copied.
+    pub fn set_stats(&mut self, stats: &Stats) {
+        let samples = &mut self.chart.samples;
+
+        let last = match samples.back() {
+            Some(last) if last.run == stats.run() => *last,
+            _ => {
+                *samples = stats.timeline(self.window as usize).into();
+                self.chart.cache.clear();
+                return;
+            },
+        };
+
+        let since = stats.since(last.generation);
+
+        if since.is_empty() || since == [last] {
+            return;
+        }
+
+        samples.pop_back();
+        samples.extend(since);
+
+        while samples.len() > self.window as usize {
+            samples.pop_front();
+        }
+
+        self.chart.cache.clear();
+    }
+
+    pub fn view(&mut self) -> Element<Message> {
+        let controls = Row::new()
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(Text::new(format!("Ventana: {} gen", self.window)).size(16))
+            .push(Slider::new(
+                &mut self.slider,
+                Self::MIN_WINDOW..=Self::MAX_WINDOW,
+                self.window,
+                Message::StatsWindowChanged,
+            ))
+            .push(
+                Button::new(&mut self.export, Text::new("Exportar estadísticas"))
+                    .on_press(Message::ExportStats)
+            );
+
+        Column::new()
+            .padding(10)
+            .spacing(5)
+            .push(
+                Canvas::new(&mut self.chart)
+                    .width(Length::Fill)
+                    .height(Length::Units(Self::HEIGHT))
+            )
+            .push(controls)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(generation: usize, population: usize) -> Sample {
+        Sample { run: 0, generation, population, births: 0, deaths: 0, width: 0, height: 0 }
+    }
+
+    fn generations(samples: &[Sample]) -> Vec<(usize, usize)> {
+        samples.iter().map(|sample| (sample.run, sample.generation)).collect()
+    }
+
+    #[test]
+    fn keeps_abandoned_runs() {
+        let mut stats = Stats::default();
+
+        for generation in 0..5 {
+            stats.record(sample(generation, 10));
+        }
+
+        // Undoing back to generation 2 and running again.
+        for generation in 2..4 {
+            stats.record(sample(generation, 20));
+        }
+
+        // Resetting.
+        stats.record(sample(0, 30));
+
+        assert_eq!(stats.run(), 2);
+        assert_eq!(stats.samples.len(), 8);
+        assert_eq!(generations(&stats.timeline(10)), [(2, 0)]);
+        assert!(stats.to_csv().starts_with("run,generation,population,births,deaths,width,height\n0,0,10,"));
+        assert!(stats.to_csv().ends_with("\n2,0,30,0,0,0,0\n"));
+    }
+
+    #[test]
+    fn follows_the_current_timeline() {
+        let mut stats = Stats::default();
+
+        for generation in 0..5 {
+            stats.record(sample(generation, 10));
+        }
+
+        for generation in 3..6 {
+            stats.record(sample(generation, 20));
+        }
+
+        for generation in 4..6 {
+            stats.record(sample(generation, 30));
+        }
+
+        assert_eq!(
+            generations(&stats.timeline(10)),
+            [(0, 0), (0, 1), (0, 2), (1, 3), (2, 4), (2, 5)],
+        );
+        assert_eq!(generations(&stats.timeline(3)), [(1, 3), (2, 4), (2, 5)]);
+        assert_eq!(generations(&stats.since(5)), [(2, 5)]);
+    }
+
+    #[test]
+    fn caps_the_samples() {
+        let mut stats = Stats::default();
+
+        for generation in 0..Stats::MAX_SAMPLES + 10 {
+            stats.record(sample(generation, 1));
+        }
+
+        assert_eq!(stats.samples.len(), Stats::MAX_SAMPLES);
+        assert_eq!(stats.samples.front().map(|sample| sample.generation), Some(10));
+    }
+}
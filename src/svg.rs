@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use iced::Color;
+
+use crate::{rgb8, Cell, Life};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extent {
+    Pattern,
+    View,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub cell_size: f32,
+    pub background: Color,
+    pub cell: Color,
+    pub grid: Option<Color>,
+    pub merge: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            cell_size: Cell::SIZE as f32,
+            background: Color::BLACK,
+            cell: Color::WHITE,
+            grid: None,
+            merge: true,
+        }
+    }
+}
+
+// Renders the live cells inside the window of `rows` x `columns` cells whose
+// top left corner is `origin`.
+pub fn render(life: &Life, origin: Cell, rows: usize, columns: usize, options: &Options) -> String {
+    let size = options.cell_size;
+    let width = columns as f32 * size;
+    let height = rows as f32 * size;
+
+    let mut cells: Vec<(usize, usize)> = life.cells
+        .iter()
+        .filter(|cell| {
+            cell.i >= origin.i && cell.i < origin.i + rows as isize
+                && cell.j >= origin.j && cell.j < origin.j + columns as isize
+        })
+        .map(|cell| ((cell.i - origin.i) as usize, (cell.j - origin.j) as usize))
+        .collect();
+
+    cells.sort_unstable();
+
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height,
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{}" height="{}" fill="{}"/>"#,
+        width, height, hex(options.background),
+    );
+
+    if options.merge {
+        let mut path = String::new();
+
+        for (i, j, rows, columns) in rectangles(&cells) {
+            let _ = write!(
+                path,
+                "M{} {}h{}v{}h-{}z",
+                j as f32 * size, i as f32 * size,
+                columns as f32 * size, rows as f32 * size, columns as f32 * size,
+            );
+        }
+
+        if !path.is_empty() {
+            let _ = writeln!(svg, r#"<path d="{}" fill="{}"/>"#, path, hex(options.cell));
+        }
+    } else {
+        for (i, j) in &cells {
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="{}"/>"#,
+                *j as f32 * size, *i as f32 * size, hex(options.cell),
+                s = size,
+            );
+        }
+    }
+
+    if let Some(color) = options.grid {
+        let mut path = String::new();
+
+        for row in 0..=rows {
+            let _ = write!(path, "M0 {}H{}", row as f32 * size, width);
+        }
+
+        for column in 0..=columns {
+            let _ = write!(path, "M{} 0V{}", column as f32 * size, height);
+        }
+
+        let _ = writeln!(
+            svg,
+            r#"<path d="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            path, hex(color), (size / 10.0).max(0.5),
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Merges horizontal runs of live cells, and identical runs in consecutive
+// rows, into `(row, column, rows, columns)` rectangles.
+fn rectangles(cells: &[(usize, usize)]) -> Vec<(usize, usize, usize, usize)> {
+    let mut runs: Vec<(usize, usize, usize)> = Vec::new();
+
+    for &(i, j) in cells {
+        match runs.last_mut() {
+            Some((row, start, length)) if *row == i && *start + *length == j => *length += 1,
+            _ => runs.push((i, j, 1)),
+        }
+    }
+
+    let mut open: BTreeMap<(usize, usize), (usize, usize)> = BTreeMap::new();
+    let mut rectangles = Vec::new();
+
+    for (i, start, length) in runs {
+        match open.get_mut(&(start, length)) {
+            Some((top, rows)) if *top + *rows == i => *rows += 1,
+            Some((top, rows)) => {
+                rectangles.push((*top, start, *rows, length));
+                *top = i;
+                *rows = 1;
+            },
+            None => {
+                open.insert((start, length), (i, 1));
+            },
+        }
+    }
+
+    rectangles.extend(
+        open.into_iter().map(|((start, length), (top, rows))| (top, start, rows, length))
+    );
+
+    rectangles
+}
+
+fn hex(color: Color) -> String {
+    let [r, g, b] = rgb8(color);
+
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    // Scattered and clustered cells from a fixed linear congruential sequence.
+    fn scatter(seed: u64, count: usize) -> Vec<(usize, usize)> {
+        let mut state = seed;
+        let mut next = move || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 33) as usize
+        };
+
+        let cells: HashSet<(usize, usize)> = (0..count).map(|_| (next() % 12, next() % 12)).collect();
+        let mut cells: Vec<(usize, usize)> = cells.into_iter().collect();
+
+        cells.sort_unstable();
+        cells
+    }
+
+    #[test]
+    fn merged_rectangles_cover_exactly_the_live_cells() {
+        for seed in 0..50 {
+            let cells = scatter(seed, 10 + seed as usize * 3);
+            let mut covered = Vec::new();
+
+            for (i, j, rows, columns) in rectangles(&cells) {
+                for row in i..i + rows {
+                    for column in j..j + columns {
+                        covered.push((row, column));
+                    }
+                }
+            }
+
+            covered.sort_unstable();
+
+            assert_eq!(covered, cells, "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn merges_runs_and_identical_rows() {
+        let block = [(0, 0), (0, 1), (1, 0), (1, 1)];
+        let split = [(0, 0), (0, 1), (1, 0), (2, 0), (2, 1)];
+
+        assert_eq!(rectangles(&block), vec![(0, 0, 2, 2)]);
+        assert_eq!(rectangles(&split).len(), 3);
+    }
+
+    #[test]
+    fn renders_the_cells_inside_the_window() {
+        let life: Life = [(0, 0), (0, 1), (1, 0), (1, 1), (5, 5), (-1, 0)]
+            .iter()
+            .map(|&(i, j)| Cell { i, j })
+            .collect();
+
+        let svg = render(&life, Cell { i: 0, j: 0 }, 3, 4, &Options::default());
+
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="30""#));
+        assert!(svg.contains(r##"<path d="M0 0h20v20h-20z" fill="#ffffff"/>"##));
+
+        let separate = render(&life, Cell { i: 0, j: 0 }, 3, 4, &Options { merge: false, ..Options::default() });
+
+        assert_eq!(separate.matches("<rect").count(), 1 + 4);
+    }
+}
@@ -0,0 +1,291 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use iced::Color;
+
+use crate::hex_color;
+
+type Preset = (&'static str, fn() -> Theme);
+
+// The colors of every canvas. `states` shade blocks of cells by how many of
+// them are alive, from sparse to full, and `fate` colors cells that survive,
+// die or are born.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub background: Color,
+    pub cell: Color,
+    pub states: [Color; Theme::STATES],
+    pub hover: Color,
+    pub preview: Color,
+    pub lines: Color,
+    pub selection: Color,
+    pub follow: Color,
+    pub trail: Color,
+    pub heat: [Color; 3],
+    pub fate: [Color; 3],
+    pub panel: Color,
+    pub text: Color,
+}
+
+// The file comes from `LIFE_THEME`, falling back to `theme.cfg` in the
+// working directory.
+pub fn path() -> PathBuf {
+    std::env::var_os("LIFE_THEME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("theme.cfg"))
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub const STATES: usize = 4;
+
+    // The presets by the name used for `base =` in theme files.
+    const PRESETS: [Preset; 4] = [
+        ("dark", Theme::dark),
+        ("light", Theme::light),
+        ("high_contrast", Theme::high_contrast),
+        ("colorblind", Theme::colorblind),
+    ];
+
+    pub fn presets() -> Vec<Theme> {
+        Self::PRESETS.iter().map(|(_, theme)| theme()).collect()
+    }
+
+    pub fn dark() -> Theme {
+        Theme {
+            name: String::from("Oscuro"),
+            background: Color::BLACK,
+            cell: Color::WHITE,
+            states: ramp(Color::WHITE),
+            hover: Color { a: 0.5, ..Color::WHITE },
+            preview: Color::from_rgba(0.4, 0.7, 1.0, 0.5),
+            lines: Color::WHITE,
+            selection: Color::from_rgb(1.0, 0.8, 0.2),
+            follow: Color::from_rgb(0.3, 1.0, 0.5),
+            trail: Color::from_rgb(1.0, 0.45, 0.2),
+            heat: [
+                Color::from_rgba(0.2, 0.3, 1.0, 0.5),
+                Color::from_rgba(0.9, 0.1, 0.1, 0.6),
+                Color::from_rgba(1.0, 0.9, 0.2, 0.7),
+            ],
+            fate: [
+                Color::from_rgb(0.3, 0.8, 1.0),
+                Color::from_rgb(1.0, 0.3, 0.3),
+                Color::from_rgba(0.3, 1.0, 0.4, 0.7),
+            ],
+            panel: Color::from_rgba(0.0, 0.0, 0.0, 0.7),
+            text: Color::from_rgb(0.8, 0.8, 0.8),
+        }
+    }
+
+    pub fn light() -> Theme {
+        let ink = Color::from_rgb(0.1, 0.1, 0.1);
+
+        Theme {
+            name: String::from("Claro"),
+            background: Color::from_rgb(0.97, 0.97, 0.95),
+            cell: ink,
+            states: ramp(ink),
+            hover: Color { a: 0.4, ..ink },
+            preview: Color::from_rgba(0.1, 0.4, 0.9, 0.5),
+            lines: Color::BLACK,
+            selection: Color::from_rgb(0.85, 0.55, 0.0),
+            follow: Color::from_rgb(0.0, 0.6, 0.2),
+            trail: Color::from_rgb(0.9, 0.4, 0.1),
+            heat: [
+                Color::from_rgba(0.2, 0.4, 0.9, 0.4),
+                Color::from_rgba(0.85, 0.1, 0.1, 0.5),
+                Color::from_rgba(0.95, 0.65, 0.0, 0.6),
+            ],
+            fate: [
+                Color::from_rgb(0.1, 0.35, 0.8),
+                Color::from_rgb(0.85, 0.15, 0.1),
+                Color::from_rgba(0.0, 0.6, 0.2, 0.7),
+            ],
+            panel: Color::from_rgba(1.0, 1.0, 1.0, 0.8),
+            text: Color::from_rgb(0.15, 0.15, 0.15),
+        }
+    }
+
+    pub fn high_contrast() -> Theme {
+        let yellow = Color::from_rgb(1.0, 1.0, 0.0);
+
+        Theme {
+            name: String::from("Alto contraste"),
+            background: Color::BLACK,
+            cell: yellow,
+            states: ramp(yellow),
+            hover: Color::from_rgba(0.0, 1.0, 1.0, 0.6),
+            preview: Color::from_rgba(1.0, 0.0, 1.0, 0.6),
+            lines: Color::WHITE,
+            selection: Color::from_rgb(0.0, 1.0, 1.0),
+            follow: Color::from_rgb(0.0, 1.0, 0.0),
+            trail: Color::from_rgb(1.0, 0.0, 0.0),
+            heat: [
+                Color::from_rgba(0.0, 0.0, 1.0, 0.6),
+                Color::from_rgba(1.0, 0.0, 0.0, 0.7),
+                Color::from_rgba(1.0, 1.0, 1.0, 0.8),
+            ],
+            fate: [
+                Color::WHITE,
+                Color::from_rgb(1.0, 0.0, 0.0),
+                Color::from_rgba(0.0, 1.0, 0.0, 0.8),
+            ],
+            panel: Color::from_rgba(0.0, 0.0, 0.0, 0.9),
+            text: Color::WHITE,
+        }
+    }
+
+    // The Okabe-Ito palette, which stays distinct under the common forms of
+    // color blindness.
+    pub fn colorblind() -> Theme {
+        let sky_blue = Color::from_rgb8(86, 180, 233);
+
+        Theme {
+            name: String::from("Daltónico"),
+            background: Color::from_rgb(0.1, 0.1, 0.12),
+            cell: sky_blue,
+            states: ramp(sky_blue),
+            hover: Color { a: 0.5, ..Color::WHITE },
+            preview: Color { a: 0.6, ..Color::from_rgb8(230, 159, 0) },
+            lines: Color::WHITE,
+            selection: Color::from_rgb8(240, 228, 66),
+            follow: Color::from_rgb8(0, 158, 115),
+            trail: Color::from_rgb8(213, 94, 0),
+            heat: [
+                Color { a: 0.5, ..Color::from_rgb8(68, 1, 84) },
+                Color { a: 0.6, ..Color::from_rgb8(33, 145, 140) },
+                Color { a: 0.7, ..Color::from_rgb8(253, 231, 37) },
+            ],
+            fate: [
+                Color::from_rgb8(0, 114, 178),
+                Color::from_rgb8(213, 94, 0),
+                Color { a: 0.7, ..Color::from_rgb8(240, 228, 66) },
+            ],
+            panel: Color::from_rgba(0.0, 0.0, 0.0, 0.7),
+            text: Color::from_rgb(0.85, 0.85, 0.85),
+        }
+    }
+
+    // Reads `key = #rrggbb` (or `#rrggbbaa`) lines on top of a `base =`
+    // preset, dark by default. A missing file gives no theme, and bad lines
+    // are reported and skipped.
+    pub fn load(path: impl AsRef<Path>) -> (Option<Theme>, Vec<String>) {
+        let mut errors = Vec::new();
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return (None, errors),
+            Err(error) => {
+                errors.push(format!("{}: {}", path.as_ref().display(), error));
+
+                return (None, errors);
+            },
+        };
+
+        let mut theme = Theme::dark();
+        let mut colors = Vec::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: &str| format!("{}:{}: {}", path.as_ref().display(), number + 1, message);
+
+            let (key, value) = match line.find('=') {
+                Some(index) => (line[..index].trim(), line[index + 1..].trim()),
+                None => {
+                    errors.push(error("expected `key = value`"));
+                    continue;
+                },
+            };
+
+            // The base is applied first wherever it appears in the file.
+            if key == "base" {
+                match Self::PRESETS.iter().find(|(name, _)| *name == value) {
+                    Some((_, base)) => theme = base(),
+                    None => errors.push(error(&format!("unknown theme `{}`", value))),
+                }
+            } else {
+                colors.push((number, key, value));
+            }
+        }
+
+        theme.name = String::from("Archivo");
+
+        // Without explicit shades, the blocks are shaded from the cell color.
+        let has_states = colors.iter().any(|(_, key, _)| key.starts_with("state_"));
+
+        for (number, key, value) in colors {
+            let error = |message: String| format!("{}:{}: {}", path.as_ref().display(), number + 1, message);
+
+            if key == "name" {
+                theme.name = String::from(value);
+                continue;
+            }
+
+            let color = match hex_color(value) {
+                Some(color) => color,
+                None => {
+                    errors.push(error(format!("invalid color `{}`", value)));
+                    continue;
+                },
+            };
+
+            let target = match key {
+                "background" => &mut theme.background,
+                "cell" => &mut theme.cell,
+                "state_1" => &mut theme.states[0],
+                "state_2" => &mut theme.states[1],
+                "state_3" => &mut theme.states[2],
+                "state_4" => &mut theme.states[3],
+                "hover" => &mut theme.hover,
+                "preview" => &mut theme.preview,
+                "lines" => &mut theme.lines,
+                "selection" => &mut theme.selection,
+                "follow" => &mut theme.follow,
+                "trail" => &mut theme.trail,
+                "heat_low" => &mut theme.heat[0],
+                "heat_mid" => &mut theme.heat[1],
+                "heat_high" => &mut theme.heat[2],
+                "survives" => &mut theme.fate[0],
+                "dies" => &mut theme.fate[1],
+                "born" => &mut theme.fate[2],
+                "panel" => &mut theme.panel,
+                "text" => &mut theme.text,
+                _ => {
+                    errors.push(error(format!("unknown key `{}`", key)));
+                    continue;
+                },
+            };
+
+            *target = color;
+        }
+
+        if !has_states {
+            theme.states = ramp(theme.cell);
+        }
+
+        (Some(theme), errors)
+    }
+}
+
+fn ramp(color: Color) -> [Color; Theme::STATES] {
+    let mut states = [color; Theme::STATES];
+
+    for (index, state) in states.iter_mut().enumerate() {
+        state.a = color.a * (0.25 + 0.75 * (index + 1) as f32 / Theme::STATES as f32);
+    }
+
+    states
+}
@@ -0,0 +1,370 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use iced::canvas::{Cache, Frame, Geometry};
+use iced::{Point, Size, Vector};
+use iced_test::raster::{Shape, Surface};
+
+use crate::density::Summary;
+use crate::selection::Selection;
+use crate::theme::Theme;
+use crate::{Cell, Life};
+
+type Shapes = Vec<(Shape, usize)>;
+type Level = HashMap<(isize, isize), Tile>;
+
+struct Tile {
+    shapes: Shapes,
+    geometry: Cache,
+    // The draw in which the tile was last visible.
+    used: usize,
+}
+
+#[derive(Default)]
+struct State {
+    levels: HashMap<usize, Level>,
+    camera: Option<(Vector, f32)>,
+    draws: usize,
+}
+
+// Square tiles of the universe, per zoom level. Level 0 draws single cells
+// and level `n` draws blocks of 2^n cells from the density summary, shaded
+// with the theme's states. Each tile keeps its merged shapes in cell
+// coordinates and its own geometry, so a generation or an edit rebuilds just
+// the tiles whose cells changed. A canvas geometry cannot be moved once
+// built, so moving the camera rebuilds the geometry of the visible tiles
+// from their shapes. Tiles out of view are dropped, least recently seen
+// first, once there are more than `CAPACITY` of them.
+#[derive(Default)]
+pub struct Tiles {
+    state: RefCell<State>,
+    theme: Theme,
+}
+
+impl Tiles {
+    // A tile is `SIDE` cells or blocks wide.
+    const SHIFT: usize = 6;
+    const SIDE: isize = 1 << Self::SHIFT;
+    const CAPACITY: usize = 4096;
+
+    pub fn invalidate(&mut self, cell: &Cell) {
+        for (level, tiles) in self.state.get_mut().levels.iter_mut() {
+            tiles.remove(&Self::tile(cell, *level));
+        }
+    }
+
+    pub fn invalidate_all(&mut self) {
+        self.state.get_mut().levels.clear();
+    }
+
+    // Keeps the shapes but rebuilds the geometry.
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.theme = theme.clone();
+
+        for tile in self.state.get_mut().levels.values_mut().flat_map(Level::values_mut) {
+            tile.geometry.clear();
+        }
+    }
+
+    // One geometry per tile overlapping `visible`. `camera` identifies the
+    // transform applied by `transform`, which must scale the frame so one
+    // unit is one cell.
+    pub fn geometries(
+        &self,
+        life: &Life,
+        visible: &Selection,
+        level: usize,
+        size: Size,
+        camera: (Vector, f32),
+        transform: impl Fn(&mut Frame),
+    ) -> Vec<Geometry> {
+        let mut state = self.state.borrow_mut();
+
+        if state.camera != Some(camera) {
+            state.camera = Some(camera);
+
+            for tile in state.levels.values_mut().flat_map(Level::values_mut) {
+                tile.geometry.clear();
+            }
+        }
+
+        let mut geometries = Vec::new();
+
+        Self::visit(&mut state, life, visible, level, |tile| {
+            geometries.push(tile.geometry.draw(size, |frame| {
+                frame.with_save(|frame| {
+                    transform(frame);
+                    Self::fill(&tile.shapes, level, &self.theme, frame);
+                });
+            }));
+        });
+
+        geometries
+    }
+
+    // Draws the tiles overlapping `visible` on a surface scaled so one unit
+    // is one cell.
+    pub fn draw(&self, life: &Life, visible: &Selection, level: usize, frame: &mut impl Surface) {
+        let mut state = self.state.borrow_mut();
+
+        Self::visit(&mut state, life, visible, level, |tile| Self::fill(&tile.shapes, level, &self.theme, frame));
+    }
+
+    // Builds the missing tiles overlapping `visible`, hands each of them to
+    // `draw` and evicts the least recently seen ones over capacity.
+    fn visit(state: &mut State, life: &Life, visible: &Selection, level: usize, mut draw: impl FnMut(&Tile)) {
+        let top_left = Self::tile(&visible.top_left, level);
+        let bottom_right = Self::tile(&visible.bottom_right, level);
+
+        let tiles = state.levels.entry(level).or_default();
+        let mut missing = HashSet::new();
+
+        for i in top_left.0..=bottom_right.0 {
+            for j in top_left.1..=bottom_right.1 {
+                if !tiles.contains_key(&(i, j)) {
+                    missing.insert((i, j));
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            let built = if level == 0 {
+                missing.into_iter().map(|key| (key, Self::cells(life, key))).collect()
+            } else {
+                Self::blocks(life, level, missing)
+            };
+
+            for (key, shapes) in built {
+                tiles.insert(key, Tile { shapes, geometry: Cache::default(), used: 0 });
+            }
+        }
+
+        state.draws += 1;
+
+        for i in top_left.0..=bottom_right.0 {
+            for j in top_left.1..=bottom_right.1 {
+                let tile = tiles.get_mut(&(i, j)).expect("visible tiles are built");
+
+                tile.used = state.draws;
+                draw(tile);
+            }
+        }
+
+        Self::evict(state);
+    }
+
+    fn evict(state: &mut State) {
+        let count: usize = state.levels.values().map(Level::len).sum();
+
+        if count <= Self::CAPACITY {
+            return;
+        }
+
+        let mut unseen: Vec<(usize, usize, (isize, isize))> = state
+            .levels
+            .iter()
+            .flat_map(|(level, tiles)| tiles.iter().map(move |(key, tile)| (tile.used, *level, *key)))
+            .filter(|(used, _, _)| *used < state.draws)
+            .collect();
+
+        unseen.sort_unstable();
+
+        for (_, level, key) in unseen.into_iter().take(count - Self::CAPACITY) {
+            if let Some(tiles) = state.levels.get_mut(&level) {
+                tiles.remove(&key);
+            }
+        }
+
+        state.levels.retain(|_, tiles| !tiles.is_empty());
+    }
+
+    fn fill(shapes: &Shapes, level: usize, theme: &Theme, frame: &mut impl Surface) {
+        for (shape, shade) in shapes {
+            let color = if level == 0 { theme.cell } else { theme.states[*shade] };
+
+            frame.fill(shape, color);
+        }
+    }
+
+    fn tile(cell: &Cell, level: usize) -> (isize, isize) {
+        let shift = level + Self::SHIFT;
+
+        (cell.i >> shift, cell.j >> shift)
+    }
+
+    fn cells(life: &Life, (i, j): (isize, isize)) -> Shapes {
+        let area = Selection::new(
+            Cell { i: i * Self::SIDE, j: j * Self::SIDE },
+            Cell { i: (i + 1) * Self::SIDE - 1, j: (j + 1) * Self::SIDE - 1 },
+        );
+
+        let cells: Vec<(Cell, usize)> = life.within(&area).into_iter().map(|cell| (cell, 0)).collect();
+
+        Self::merge(cells, 1.0)
+            .into_iter()
+            .map(|(shade, rectangles)| (Shape::Rectangles(rectangles), shade))
+            .collect()
+    }
+
+    // One pass over the summary fills every missing tile of the level.
+    fn blocks(life: &Life, level: usize, missing: HashSet<(isize, isize)>) -> HashMap<(isize, isize), Shapes> {
+        let summary = Summary::new(life, level);
+        let side = (1u64 << summary.level()) as f32;
+
+        let mut shaded: HashMap<(isize, isize), Vec<(Cell, usize)>> = missing
+            .into_iter()
+            .map(|key| (key, Vec::new()))
+            .collect();
+
+        for (block, density) in summary.blocks() {
+            let key = (block.i >> Self::SHIFT, block.j >> Self::SHIFT);
+
+            if let Some(blocks) = shaded.get_mut(&key) {
+                let shade = (density.sqrt() * Theme::STATES as f32).ceil() as usize;
+
+                blocks.push((block, shade.clamp(1, Theme::STATES) - 1));
+            }
+        }
+
+        shaded
+            .into_iter()
+            .map(|(key, blocks)| {
+                let shapes = Self::merge(blocks, side)
+                    .into_iter()
+                    .map(|(shade, rectangles)| (Shape::Rectangles(rectangles), shade))
+                    .collect();
+
+                (key, shapes)
+            })
+            .collect()
+    }
+
+    // Joins horizontal runs of squares with the same shade into rectangles,
+    // grouped by shade.
+    fn merge(mut squares: Vec<(Cell, usize)>, side: f32) -> Vec<(usize, Vec<(Point, Size)>)> {
+        squares.sort_by_key(|(cell, shade)| (*shade, cell.i, cell.j));
+
+        let mut shades: Vec<(usize, Vec<(Point, Size)>)> = Vec::new();
+        let mut run: Option<(Cell, usize, isize)> = None;
+
+        let close = |run: Option<(Cell, usize, isize)>, shades: &mut Vec<(usize, Vec<(Point, Size)>)>| {
+            if let Some((start, shade, length)) = run {
+                let rectangle = (
+                    Point::new(start.j as f32 * side, start.i as f32 * side),
+                    Size::new(length as f32 * side, side),
+                );
+
+                match shades.last_mut() {
+                    Some((last, rectangles)) if *last == shade => rectangles.push(rectangle),
+                    _ => shades.push((shade, vec![rectangle])),
+                }
+            }
+        };
+
+        for (cell, shade) in squares {
+            run = match run {
+                Some((start, run_shade, length))
+                    if run_shade == shade && start.i == cell.i && start.j + length == cell.j =>
+                {
+                    Some((start, shade, length + 1))
+                },
+                _ => {
+                    close(run, &mut shades);
+                    Some((cell, shade, 1))
+                },
+            };
+        }
+
+        close(run, &mut shades);
+
+        shades
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The cells of `rows` x `columns` tiles from tile (`top`, `left`) at
+    // level 0.
+    fn tiles(top: isize, left: isize, rows: isize, columns: isize) -> Selection {
+        Selection::new(
+            Cell { i: top * Tiles::SIDE, j: left * Tiles::SIDE },
+            Cell { i: (top + rows) * Tiles::SIDE - 1, j: (left + columns) * Tiles::SIDE - 1 },
+        )
+    }
+
+    fn visit(tiles: &mut Tiles, life: &Life, visible: &Selection, level: usize) {
+        Tiles::visit(tiles.state.get_mut(), life, visible, level, |_| {});
+    }
+
+    fn has(tiles: &mut Tiles, level: usize, key: (isize, isize)) -> bool {
+        tiles.state.get_mut().levels.get(&level).is_some_and(|level| level.contains_key(&key))
+    }
+
+    fn count(tiles: &mut Tiles) -> usize {
+        tiles.state.get_mut().levels.values().map(Level::len).sum()
+    }
+
+    #[test]
+    fn edits_invalidate_only_their_tiles() {
+        let life: Life = [Cell { i: 5, j: 5 }, Cell { i: -1, j: 70 }].iter().copied().collect();
+        let mut cache = Tiles::default();
+
+        visit(&mut cache, &life, &tiles(-1, -1, 3, 3), 0);
+        visit(&mut cache, &life, &tiles(-1, -1, 3, 3), 1);
+
+        assert_eq!(count(&mut cache), 9 + 4);
+
+        cache.invalidate(&Cell { i: 5, j: 5 });
+
+        assert!(!has(&mut cache, 0, (0, 0)));
+        assert!(!has(&mut cache, 1, (0, 0)));
+        assert_eq!(count(&mut cache), 8 + 3);
+
+        cache.invalidate(&Cell { i: -1, j: 70 });
+
+        assert!(!has(&mut cache, 0, (-1, 1)));
+        assert!(!has(&mut cache, 1, (-1, 0)));
+        assert!(has(&mut cache, 0, (-1, 0)) && has(&mut cache, 0, (1, 1)));
+        assert_eq!(count(&mut cache), 7 + 2);
+
+        visit(&mut cache, &life, &tiles(-1, -1, 3, 3), 0);
+
+        assert_eq!(count(&mut cache), 9 + 2);
+    }
+
+    #[test]
+    fn eviction_keeps_the_most_recently_seen_tiles() {
+        let life = Life::default();
+        let mut cache = Tiles::default();
+
+        let (first, second, third) = (tiles(0, 0, 50, 80), tiles(100, 0, 2, 48), tiles(200, 0, 2, 10));
+
+        visit(&mut cache, &life, &first, 0);
+        visit(&mut cache, &life, &second, 0);
+
+        assert_eq!(count(&mut cache), Tiles::CAPACITY);
+
+        visit(&mut cache, &life, &first, 0);
+        visit(&mut cache, &life, &third, 0);
+
+        assert_eq!(count(&mut cache), Tiles::CAPACITY);
+
+        for i in 0..50 {
+            for j in 0..80 {
+                assert!(has(&mut cache, 0, (i, j)), "{:?} was evicted", (i, j));
+            }
+        }
+
+        for i in 200..202 {
+            for j in 0..10 {
+                assert!(has(&mut cache, 0, (i, j)), "{:?} was evicted", (i, j));
+            }
+        }
+
+        let kept = (100..102).flat_map(|i| (0..48).map(move |j| (i, j))).filter(|key| has(&mut cache, 0, *key)).count();
+
+        assert_eq!(kept, 96 - 20);
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashSet;
+
+use iced::{button, Button, Element, Row, Text};
+
+use crate::selection::Selection;
+use crate::{Cell, Life, Message};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    Freehand,
+    Line,
+    Rectangle,
+    Ellipse,
+    Fill,
+    Select,
+    Follow,
+    Inspect,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 8] = [
+        Tool::Freehand,
+        Tool::Line,
+        Tool::Rectangle,
+        Tool::Ellipse,
+        Tool::Fill,
+        Tool::Select,
+        Tool::Follow,
+        Tool::Inspect,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Freehand => "Lápiz",
+            Tool::Line => "Línea",
+            Tool::Rectangle => "Rectángulo",
+            Tool::Ellipse => "Elipse",
+            Tool::Fill => "Relleno",
+            Tool::Select => "Seleccionar",
+            Tool::Follow => "Seguir",
+            Tool::Inspect => "Inspeccionar",
+        }
+    }
+
+    // The cells drawn when dragging from one cell to another, for the tools
+    // that draw a shape on release.
+    pub fn shape(self, from: Cell, to: Cell) -> Vec<Cell> {
+        match self {
+            Tool::Line => line(from, to),
+            Tool::Rectangle => rectangle(from, to),
+            Tool::Ellipse => ellipse(from, to),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn is_shape(self) -> bool {
+        matches!(self, Tool::Line | Tool::Rectangle | Tool::Ellipse)
+    }
+}
+
+// Bresenham's line, including both ends.
+pub fn line(from: Cell, to: Cell) -> Vec<Cell> {
+    let (di, dj) = ((to.i - from.i).abs(), (to.j - from.j).abs());
+    let (step_i, step_j) = ((to.i - from.i).signum(), (to.j - from.j).signum());
+
+    let mut cells = Vec::with_capacity(di.max(dj) as usize + 1);
+    let mut cell = from;
+    let mut error = dj - di;
+
+    loop {
+        cells.push(cell);
+
+        if cell == to {
+            return cells;
+        }
+
+        let doubled = 2 * error;
+
+        if doubled > -di {
+            error -= di;
+            cell.j += step_j;
+        }
+
+        if doubled < dj {
+            error += dj;
+            cell.i += step_i;
+        }
+    }
+}
+
+pub fn rectangle(from: Cell, to: Cell) -> Vec<Cell> {
+    let Selection { top_left, bottom_right } = Selection::new(from, to);
+
+    let mut cells = Vec::new();
+
+    for j in top_left.j..=bottom_right.j {
+        cells.push(Cell { i: top_left.i, j });
+        cells.push(Cell { i: bottom_right.i, j });
+    }
+
+    for i in top_left.i + 1..bottom_right.i {
+        cells.push(Cell { i, j: top_left.j });
+        cells.push(Cell { i, j: bottom_right.j });
+    }
+
+    cells
+}
+
+// The ellipse inscribed in the rectangle between both cells, traced with
+// Zingl's integer midpoint algorithm.
+pub fn ellipse(from: Cell, to: Cell) -> Vec<Cell> {
+    let Selection { top_left, bottom_right } = Selection::new(from, to);
+
+    // The algorithm drops the ends of ellipses one or two cells thick, which
+    // are just their bounding box anyway.
+    if bottom_right.i - top_left.i < 2 || bottom_right.j - top_left.j < 2 {
+        return rectangle(from, to);
+    }
+
+    let mut a = (bottom_right.j - top_left.j) as i64;
+    let b = (bottom_right.i - top_left.i) as i64;
+    let mut b1 = b & 1;
+
+    let mut dx = 4 * (1 - a) * b * b;
+    let mut dy = 4 * (b1 + 1) * a * a;
+    let mut error = dx + dy + b1 * a * a;
+
+    let (mut x0, mut x1) = (top_left.j as i64, bottom_right.j as i64);
+    let mut y0 = top_left.i as i64 + (b + 1) / 2;
+    let mut y1 = y0 - b1;
+
+    a = 8 * a * a;
+    b1 = 8 * b * b;
+
+    let mut cells = HashSet::new();
+    let mut plot = |x: i64, y: i64| {
+        cells.insert(Cell { i: y as isize, j: x as isize });
+    };
+
+    loop {
+        plot(x1, y0);
+        plot(x0, y0);
+        plot(x0, y1);
+        plot(x1, y1);
+
+        let doubled = 2 * error;
+
+        if doubled <= dy {
+            y0 += 1;
+            y1 -= 1;
+            dy += a;
+            error += dy;
+        }
+
+        if doubled >= dx || 2 * error > dy {
+            x0 += 1;
+            x1 -= 1;
+            dx += b1;
+            error += dx;
+        }
+
+        if x0 > x1 {
+            break;
+        }
+    }
+
+    // Narrow ellipses leave the loop before reaching the ends of their long
+    // axis, which are finished here up to the edges of the box.
+    while y0 - y1 <= b {
+        plot(x0 - 1, y0);
+        plot(x1 + 1, y0);
+        plot(x0 - 1, y1);
+        plot(x1 + 1, y1);
+
+        y0 += 1;
+        y1 -= 1;
+    }
+
+    cells.into_iter().collect()
+}
+
+// The 4-connected region of cells sharing the state of `start`, limited to
+// `bounds`. Gives up on regions larger than `limit` cells.
+pub fn flood_fill(life: &Life, start: Cell, bounds: Selection, limit: usize) -> Option<Vec<Cell>> {
+    if !bounds.contains(&start) {
+        return None;
+    }
+
+    let alive = life.contains(&start);
+
+    let mut visited = HashSet::new();
+    let mut pending = vec![start];
+
+    visited.insert(start);
+
+    while let Some(cell) = pending.pop() {
+        if visited.len() > limit {
+            return None;
+        }
+
+        let neighbors = [
+            Cell { i: cell.i - 1, j: cell.j },
+            Cell { i: cell.i + 1, j: cell.j },
+            Cell { i: cell.i, j: cell.j - 1 },
+            Cell { i: cell.i, j: cell.j + 1 },
+        ];
+
+        for neighbor in neighbors.iter() {
+            if bounds.contains(neighbor)
+                && life.contains(neighbor) == alive
+                && visited.insert(*neighbor)
+            {
+                pending.push(*neighbor);
+            }
+        }
+    }
+
+    Some(visited.into_iter().collect())
+}
+
+#[derive(Default)]
+pub struct Palette {
+    buttons: [button::State; 8],
+}
+
+impl Palette {
+    pub fn view(&mut self, active: Tool) -> Element<Message> {
+        self.buttons
+            .iter_mut()
+            .zip(Tool::ALL.iter())
+            .fold(Row::new().spacing(5), |row, (state, tool)| {
+                let button = Button::new(state, Text::new(tool.name()).size(16));
+
+                row.push(if *tool == active {
+                    button
+                } else {
+                    button.on_press(Message::SelectTool(*tool))
+                })
+            })
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn cell(i: isize, j: isize) -> Cell {
+        Cell { i, j }
+    }
+
+    fn set(cells: Vec<Cell>) -> HashSet<(isize, isize)> {
+        cells.into_iter().map(|cell| (cell.i, cell.j)).collect()
+    }
+
+    fn is_connected(cells: &[Cell]) -> bool {
+        cells.windows(2).all(|pair| (pair[0].i - pair[1].i).abs() <= 1 && (pair[0].j - pair[1].j).abs() <= 1)
+    }
+
+    #[test]
+    fn lines_join_their_ends() {
+        for &(from, to) in &[
+            (cell(0, 0), cell(2, 7)),
+            (cell(0, 0), cell(7, 2)),
+            (cell(3, -4), cell(-5, 1)),
+            (cell(1, 1), cell(1, 1)),
+        ] {
+            let cells = line(from, to);
+            let steps = (to.i - from.i).abs().max((to.j - from.j).abs());
+
+            assert_eq!(cells.first(), Some(&from));
+            assert_eq!(cells.last(), Some(&to));
+            assert_eq!(cells.len() as isize, steps + 1);
+            assert!(is_connected(&cells));
+        }
+    }
+
+    #[test]
+    fn lines_step_along_their_major_axis() {
+        let shallow = line(cell(0, 0), cell(2, 7));
+        let steep = line(cell(0, 0), cell(-7, -2));
+
+        assert_eq!(shallow.iter().map(|cell| cell.j).collect::<Vec<_>>(), (0..=7).collect::<Vec<_>>());
+        assert!(shallow.windows(2).all(|pair| pair[1].i - pair[0].i <= 1 && pair[1].i >= pair[0].i));
+
+        assert_eq!(steep.iter().map(|cell| cell.i).collect::<Vec<_>>(), (-7..=0).rev().collect::<Vec<_>>());
+        assert!(steep.windows(2).all(|pair| pair[0].j - pair[1].j <= 1 && pair[1].j <= pair[0].j));
+
+        assert_eq!(set(line(cell(0, 0), cell(3, 3))), set((0..=3).map(|k| cell(k, k)).collect()));
+    }
+
+    #[test]
+    fn ellipses_are_symmetric() {
+        for &(height, width) in &[(6, 10), (7, 7), (3, 12), (12, 3), (5, 8)] {
+            let cells = set(ellipse(cell(0, 0), cell(height, width)));
+
+            for &(i, j) in &cells {
+                assert!((0..=height).contains(&i) && (0..=width).contains(&j), "{:?} outside", (i, j));
+                assert!(cells.contains(&(height - i, j)), "{}x{} not symmetric at {:?}", height, width, (i, j));
+                assert!(cells.contains(&(i, width - j)), "{}x{} not symmetric at {:?}", height, width, (i, j));
+            }
+
+            assert!(cells.iter().any(|&(i, _)| i == 0) && cells.iter().any(|&(i, _)| i == height));
+            assert!(cells.iter().any(|&(_, j)| j == 0) && cells.iter().any(|&(_, j)| j == width));
+        }
+    }
+
+    #[test]
+    fn thin_ellipses_fill_their_box() {
+        let row = set(ellipse(cell(4, 0), cell(4, 5)));
+        let column = set(ellipse(cell(0, 2), cell(5, 2)));
+        let double = set(ellipse(cell(0, 0), cell(1, 5)));
+
+        assert_eq!(row, (0..=5).map(|j| (4, j)).collect());
+        assert_eq!(column, (0..=5).map(|i| (i, 2)).collect());
+        assert_eq!(double, (0..=1).flat_map(|i| (0..=5).map(move |j| (i, j))).collect());
+        assert_eq!(set(ellipse(cell(3, 3), cell(3, 3))), [(3, 3)].iter().copied().collect());
+    }
+
+    #[test]
+    fn fills_stop_at_closed_borders() {
+        let border: Life = rectangle(cell(0, 0), cell(4, 4)).into_iter().collect();
+        let bounds = Selection::new(cell(-100, -100), cell(100, 100));
+
+        let inside = flood_fill(&border, cell(2, 2), bounds, 1_000).unwrap();
+        let wall = flood_fill(&border, cell(0, 3), bounds, 1_000).unwrap();
+
+        assert_eq!(set(inside), (1..=3).flat_map(|i| (1..=3).map(move |j| (i, j))).collect());
+        assert_eq!(set(wall), set(rectangle(cell(0, 0), cell(4, 4))));
+    }
+
+    #[test]
+    fn fills_stay_within_bounds_and_limit() {
+        let empty = Life::default();
+        let bounds = Selection::new(cell(-2, -2), cell(2, 2));
+
+        assert_eq!(flood_fill(&empty, cell(0, 0), bounds, 1_000).map(|cells| cells.len()), Some(25));
+        assert_eq!(flood_fill(&empty, cell(0, 0), bounds, 10), None);
+        assert_eq!(flood_fill(&empty, cell(5, 5), bounds, 1_000), None);
+
+        let open = Selection::new(cell(-1 << 30, -1 << 30), cell(1 << 30, 1 << 30));
+
+        assert_eq!(flood_fill(&empty, cell(0, 0), open, 1_000), None);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use iced::{button, slider, Align, Button, Element, Row, Slider, Text};
+
+use crate::selection::Selection;
+use crate::{Cell, Message};
+
+// How a trail fades with the generations since the cell died.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fade {
+    Linear,
+    Quadratic,
+    Exponential,
+}
+
+impl Fade {
+    pub fn name(self) -> &'static str {
+        match self {
+            Fade::Linear => "lineal",
+            Fade::Quadratic => "cuadrático",
+            Fade::Exponential => "exponencial",
+        }
+    }
+
+    pub fn next(self) -> Fade {
+        match self {
+            Fade::Linear => Fade::Quadratic,
+            Fade::Quadratic => Fade::Exponential,
+            Fade::Exponential => Fade::Linear,
+        }
+    }
+
+    // The opacity, from 1 for a cell that just died toward 0 at `length`.
+    pub fn alpha(self, age: usize, length: usize) -> f32 {
+        let remaining = 1.0 - age as f32 / length.max(1) as f32;
+
+        match self {
+            Fade::Linear => remaining,
+            Fade::Quadratic => remaining * remaining,
+            Fade::Exponential => (-4.0 * (1.0 - remaining)).exp() * remaining,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub is_enabled: bool,
+    pub length: u16,
+    pub fade: Fade,
+}
+
+impl Settings {
+    pub const MAX_LENGTH: u16 = 200;
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            is_enabled: false,
+            length: 20,
+            fade: Fade::Linear,
+        }
+    }
+}
+
+// The generation in which each recently dead cell died.
+#[derive(Debug, Clone, Default)]
+pub struct Trails {
+    died: HashMap<Cell, usize>,
+}
+
+impl Trails {
+    pub fn died(&mut self, cell: Cell, generation: usize) {
+        self.died.insert(cell, generation);
+    }
+
+    pub fn born(&mut self, cell: &Cell) {
+        self.died.remove(cell);
+    }
+
+    pub fn clear(&mut self) {
+        self.died.clear();
+    }
+
+    // Forgets the cells that died too long ago, or after `generation` when
+    // the simulation went back.
+    pub fn prune(&mut self, generation: usize, length: usize) {
+        self.died.retain(|_, died| *died <= generation && generation - *died < length);
+    }
+
+    // The visible trail cells grouped by age.
+    pub fn visible(&self, area: &Selection, generation: usize, length: usize) -> Vec<(usize, Vec<Cell>)> {
+        let mut ages: Vec<Vec<Cell>> = vec![Vec::new(); length];
+
+        for (cell, died) in &self.died {
+            if *died <= generation && generation - died < length && area.contains(cell) {
+                ages[generation - died].push(*cell);
+            }
+        }
+
+        ages.into_iter()
+            .enumerate()
+            .filter(|(_, cells)| !cells.is_empty())
+            .collect()
+    }
+}
+
+#[derive(Default)]
+pub struct Bar {
+    toggle: button::State,
+    length: slider::State,
+    fade: button::State,
+}
+
+impl Bar {
+    pub fn view(&mut self, settings: Settings) -> Element<Message> {
+        Row::new()
+            .padding(10)
+            .spacing(10)
+            .align_items(Align::Center)
+            .push(
+                Button::new(
+                    &mut self.toggle,
+                    Text::new(if settings.is_enabled { "Ocultar estela" } else { "Mostrar estela" }),
+                )
+                .on_press(Message::ToggleTrails)
+            )
+            .push(Slider::new(
+                &mut self.length,
+                1..=Settings::MAX_LENGTH,
+                settings.length,
+                Message::TrailLengthChanged,
+            ))
+            .push(Text::new(format!("{} gen de estela", settings.length)).size(16))
+            .push(
+                Button::new(&mut self.fade, Text::new(format!("Desvanecido {}", settings.fade.name())))
+                    .on_press(Message::NextFade)
+            )
+            .into()
+    }
+}
@@ -0,0 +1,54 @@
+// How far the view can zoom. At a scaling of 1 a cell is `Cell::SIZE`
+// pixels wide, so the default minimum puts 2^20 cells in a pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            min: 0.1 / (1 << 20) as f32,
+            max: 2.0,
+        }
+    }
+}
+
+impl Limits {
+    // Reads `LIFE_MIN_SCALING` and `LIFE_MAX_SCALING`; a missing or invalid
+    // value keeps its default.
+    pub fn load() -> (Limits, Vec<String>) {
+        let mut limits = Limits::default();
+        let mut errors = Vec::new();
+
+        let mut read = |name: &str, value: &mut f32| {
+            if let Some(text) = std::env::var_os(name) {
+                let text = text.to_string_lossy();
+
+                match text.trim().parse::<f32>() {
+                    Ok(parsed) if parsed.is_finite() && parsed > 0.0 => *value = parsed,
+                    _ => errors.push(format!("{}: invalid scaling `{}`", name, text)),
+                }
+            }
+        };
+
+        read("LIFE_MIN_SCALING", &mut limits.min);
+        read("LIFE_MAX_SCALING", &mut limits.max);
+
+        if limits.min > limits.max {
+            errors.push(format!(
+                "LIFE_MIN_SCALING ({}) is larger than LIFE_MAX_SCALING ({})",
+                limits.min, limits.max,
+            ));
+
+            limits = Limits::default();
+        }
+
+        (limits, errors)
+    }
+
+    pub fn clamp(&self, scaling: f32) -> f32 {
+        scaling.clamp(self.min, self.max)
+    }
+}